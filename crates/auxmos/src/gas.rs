@@ -5,6 +5,9 @@ pub mod mixture;
 
 pub mod types;
 
+#[cfg(feature = "fuzz_helpers")]
+pub mod fuzz;
+
 use auxtools::*;
 
 pub use types::*;
@@ -13,9 +16,12 @@ use fxhash::FxBuildHasher;
 
 use parking_lot::{const_rwlock, RwLock};
 
-pub use mixture::Mixture;
+pub use mixture::{Mixture, ScratchMix};
 
-use std::{cell::RefCell, collections::HashSet};
+use std::{
+	cell::RefCell,
+	collections::{HashMap, HashSet, VecDeque},
+};
 
 pub type GasIDX = usize;
 
@@ -35,8 +41,19 @@ static NEXT_GAS_IDS: RwLock<Option<Vec<usize>>> = const_rwlock(None);
 
 thread_local! {
 	static REGISTERED_GAS_MIXES: RefCell<Option<HashSet<u32, FxBuildHasher>>> = RefCell::new(None);
+	// Smoothed temperatures for display, by arena index; kept out of the mixtures so physics never sees them.
+	static SMOOTHED_TEMPERATURES: RefCell<HashMap<usize, f32, FxBuildHasher>> = RefCell::new(Default::default());
+	static GAS_COUNT_SAMPLES: RefCell<VecDeque<usize>> = RefCell::new(VecDeque::with_capacity(MAX_GAS_COUNT_SAMPLES));
+	// Copies of mixtures taken by `GasArena::snapshot`, by handle; freed handles are left as None to be reused.
+	static GAS_SNAPSHOTS: RefCell<Vec<Option<Mixture>>> = const { RefCell::new(Vec::new()) };
+	// Generation of each arena slot, bumped every time it's freed; slots past the end are on generation 0.
+	#[cfg(feature = "generational_gas_mixtures")]
+	static GAS_GENERATIONS: RefCell<Vec<u32>> = const { RefCell::new(Vec::new()) };
 }
 
+/// How many samples of `amt_gases` `sample_gas_count` keeps for `gas_count_trend`; older ones are dropped.
+const MAX_GAS_COUNT_SAMPLES: usize = 60;
+
 //is registered mix may be called when byond's del datum runs after world shutdown is done.
 //this is allowed to fail because of that
 fn is_registered_mix(i: u32) -> bool {
@@ -57,6 +74,30 @@ fn register_mix(v: &Value) {
 	});
 }
 
+/// Generations are stored in DM as plain numbers, so they wrap before they stop being exact.
+#[cfg(feature = "generational_gas_mixtures")]
+const MAX_GAS_GENERATION: u32 = (1 << 24) - 1;
+
+#[cfg(feature = "generational_gas_mixtures")]
+fn slot_generation(idx: usize) -> u32 {
+	GAS_GENERATIONS.with(|generations| generations.borrow().get(idx).copied().unwrap_or(0))
+}
+
+#[cfg(feature = "generational_gas_mixtures")]
+fn bump_slot_generation(idx: usize) {
+	GAS_GENERATIONS.with(|generations| {
+		let mut generations = generations.borrow_mut();
+		if generations.len() <= idx {
+			generations.resize(idx + 1, 0);
+		}
+		generations[idx] = if generations[idx] == MAX_GAS_GENERATION {
+			0
+		} else {
+			generations[idx] + 1
+		};
+	});
+}
+
 //Unregister mix may be called when byond's del datum runs after world shutdown is done.
 //this is allowed to fail because of that
 fn unregister_mix(i: u32) {
@@ -81,6 +122,11 @@ fn _shut_down_gases() {
 	GAS_MIXTURES.write().as_mut().unwrap().clear();
 	NEXT_GAS_IDS.write().as_mut().unwrap().clear();
 	REGISTERED_GAS_MIXES.with(|thing| *thing.borrow_mut() = None);
+	#[cfg(feature = "generational_gas_mixtures")]
+	GAS_GENERATIONS.with(|generations| generations.borrow_mut().clear());
+	SMOOTHED_TEMPERATURES.with(|temps| temps.borrow_mut().clear());
+	GAS_COUNT_SAMPLES.with(|samples| samples.borrow_mut().clear());
+	GAS_SNAPSHOTS.with(|snapshots| snapshots.borrow_mut().clear());
 }
 
 impl GasArena {
@@ -219,7 +265,7 @@ impl GasArena {
 	/// If not called from the main thread
 	/// If `NEXT_GAS_IDS` is not initialized, somehow.
 	pub fn register_mix(mix: &Value) -> DMResult {
-		if NEXT_GAS_IDS.read().as_ref().unwrap().is_empty() {
+		let idx = if NEXT_GAS_IDS.read().as_ref().unwrap().is_empty() {
 			let mut lock = GAS_MIXTURES.write();
 			let gas_mixtures = lock.as_mut().unwrap();
 			let next_idx = gas_mixtures.len();
@@ -234,10 +280,7 @@ impl GasArena {
 						)
 					})?,
 			)));
-			mix.set(
-				byond_string!("_extools_pointer_gasmixture"),
-				f32::from_bits(next_idx as u32),
-			)?;
+			next_idx
 		} else {
 			let idx = {
 				let mut next_gas_ids = NEXT_GAS_IDS.write();
@@ -261,11 +304,17 @@ impl GasArena {
 							)
 						})?,
 				);
-			mix.set(
-				byond_string!("_extools_pointer_gasmixture"),
-				f32::from_bits(idx as u32),
-			)?;
-		}
+			idx
+		};
+		mix.set(
+			byond_string!("_extools_pointer_gasmixture"),
+			f32::from_bits(idx as u32),
+		)?;
+		#[cfg(feature = "generational_gas_mixtures")]
+		mix.set(
+			byond_string!("_extools_generation_gasmixture"),
+			slot_generation(idx) as f32,
+		)?;
 		register_mix(mix);
 		rayon::spawn(|| {
 			if NEXT_GAS_IDS.read().as_ref().unwrap().is_empty() {
@@ -315,22 +364,103 @@ impl GasArena {
 						let mut next_gas_ids = NEXT_GAS_IDS.write();
 						next_gas_ids.as_mut().unwrap().push(idx as usize);
 					}
+					#[cfg(feature = "generational_gas_mixtures")]
+					bump_slot_generation(idx as usize);
+					SMOOTHED_TEMPERATURES.with(|temps| temps.borrow_mut().remove(&(idx as usize)));
 					unregister_mix(mix);
 				}
 			}
 		}
 	}
+	/// Copies the given gas mixture into the snapshot arena, kept apart from the gas mixtures, and returns a handle to the copy.
+	/// Handles of freed snapshots are reused.
+	/// # Errors
+	/// If no such gas mixture exists.
+	/// # Panics
+	/// If not called from the main thread.
+	pub fn snapshot(id: usize) -> Result<usize, Runtime> {
+		let copy = Self::with_gas_mixture(id, |mix| Ok(mix.clone()))?;
+		Ok(GAS_SNAPSHOTS.with(|snapshots| {
+			let mut snapshots = snapshots.borrow_mut();
+			if let Some(handle) = snapshots.iter().position(Option::is_none) {
+				snapshots[handle] = Some(copy);
+				handle
+			} else {
+				snapshots.push(Some(copy));
+				snapshots.len() - 1
+			}
+		}))
+	}
+	/// Copies the snapshot with the given handle back into the given gas mixture, as `Mixture::copy_from_mutable` does,
+	/// so immutable mixtures aren't changed and the volume is left alone. The snapshot is kept until freed.
+	/// # Errors
+	/// If no such gas mixture or snapshot exists.
+	/// # Panics
+	/// If not called from the main thread.
+	pub fn restore(id: usize, handle: usize) -> Result<(), Runtime> {
+		GAS_SNAPSHOTS.with(|snapshots| {
+			let snapshots = snapshots.borrow();
+			let snapshot = snapshots
+				.get(handle)
+				.and_then(Option::as_ref)
+				.ok_or_else(|| runtime!("No gas snapshot with handle {} exists!", handle))?;
+			Self::with_gas_mixture_mut(id, |mix| {
+				mix.copy_from_mutable(snapshot);
+				Ok(())
+			})
+		})
+	}
+	/// Frees the snapshot with the given handle, so the handle can be reused. Returns whether there was one to free.
+	/// # Panics
+	/// If not called from the main thread.
+	pub fn free_snapshot(handle: usize) -> bool {
+		GAS_SNAPSHOTS.with(|snapshots| {
+			snapshots
+				.borrow_mut()
+				.get_mut(handle)
+				.and_then(Option::take)
+				.is_some()
+		})
+	}
 }
 
-/// Gets the mix for the given value, and calls the provided closure with a reference to that mix as an argument.
+/// Whether the given gas mixture datum is still registered and its arena slot is still in use,
+/// i.e. whether anything done through it will actually happen to its own mixture rather than erroring or hitting a recycled slot.
+/// # Panics
+/// If not called from the main thread.
+pub fn is_live_gas_mixture(mix: &Value) -> bool {
+	if !is_registered_mix(unsafe { mix.raw.data.id }) {
+		return false;
+	}
+	let idx = match mix_index(mix) {
+		Ok(idx) => idx,
+		Err(_) => return false,
+	};
+	idx < tot_gases() && !NEXT_GAS_IDS.read().as_ref().unwrap().contains(&idx)
+}
+
+/// The index in the arena of the given gas mixture datum's mixture.
+/// With `generational_gas_mixtures`, also checks the datum's generation against its slot's,
+/// so a handle to a mixture that's since been unregistered can't end up operating on whatever mixture got its slot next.
+/// That needs `/datum/gas_mixture` to declare `_extools_generation_gasmixture` alongside `_extools_pointer_gasmixture`.
 /// # Errors
-/// If a gasmixture ID is not a number or the callback returns an error.
-pub fn with_mix<T, F>(mix: &Value, f: F) -> Result<T, Runtime>
-where
-	F: FnMut(&Mixture) -> Result<T, Runtime>,
-{
-	GasArena::with_gas_mixture(
-		mix.get_number(byond_string!("_extools_pointer_gasmixture"))
+/// If a gasmixture ID or generation is not a number, or the handle is stale.
+pub fn mix_index(mix: &Value) -> Result<usize, Runtime> {
+	let idx = mix
+		.get_number(byond_string!("_extools_pointer_gasmixture"))
+		.map_err(|_| {
+			runtime!(
+				"Attempt to interpret non-number value as number {} {}:{}",
+				std::file!(),
+				std::line!(),
+				std::column!()
+			)
+		})?
+		.to_bits() as usize;
+	#[cfg(feature = "generational_gas_mixtures")]
+	{
+		let generation = mix
+			.get_number(byond_string!("_extools_generation_gasmixture"))
 			.map_err(|_| {
 				runtime!(
 					"Attempt to interpret non-number value as number {} {}:{}",
@@ -338,10 +468,89 @@ where
 					std::line!(),
 					std::column!()
 				)
-			})?
-			.to_bits() as usize,
-		f,
-	)
+			})? as u32;
+		let current = slot_generation(idx);
+		if generation != current {
+			return Err(runtime!(
+				"Stale gas mixture handle: slot {} is on generation {}, but this handle is from generation {}",
+				idx,
+				current,
+				generation
+			));
+		}
+	}
+	Ok(idx)
+}
+
+/// Feeds the given temperature into the exponential moving average kept for the mix at the given arena index, and returns the new average.
+/// `alpha` is how much the new reading counts, between 0 and 1; the first reading for a mix is taken as is.
+/// Only for displays; the averages are forgotten when their mixes are unregistered.
+/// # Panics
+/// If not called from the main thread.
+pub fn smoothed_temperature(idx: usize, temperature: f32, alpha: f32) -> f32 {
+	let alpha = alpha.clamp(0.0, 1.0);
+	SMOOTHED_TEMPERATURES.with(|temps| {
+		let mut temps = temps.borrow_mut();
+		let smoothed = temps.get(&idx).map_or(temperature, |&previous| {
+			previous + alpha * (temperature - previous)
+		});
+		temps.insert(idx, smoothed);
+		smoothed
+	})
+}
+
+/// The datum IDs of every registered gas mixture, i.e. every one attached to a `/datum/gas_mixture`.
+/// # Panics
+/// If not called from the main thread.
+pub fn registered_gas_mixtures() -> Vec<u32> {
+	REGISTERED_GAS_MIXES.with(|thin| {
+		thin.borrow()
+			.as_ref()
+			.expect("Wrong thread tried to access REGISTERED_GAS_MIXES, must be the main thread!")
+			.iter()
+			.copied()
+			.collect()
+	})
+}
+
+/// The `/datum/gas_mixture` with the given datum ID, or `None` if it's since been unregistered.
+pub fn registered_gas_mixture(id: u32) -> Option<Value> {
+	use raw_types::values::{ValueData, ValueTag};
+	if is_registered_mix(id) {
+		Some(unsafe { Value::new(ValueTag::Datum, ValueData { id }) })
+	} else {
+		None
+	}
+}
+
+/// Makes a new `/datum/gas_mixture` through `/proc/auxtools_new_gas_mixture`, since auxtools can't make datums itself.
+/// # Errors
+/// If that proc doesn't exist or runtimes.
+pub fn new_gas_mixture() -> DMResult {
+	Proc::find(byond_string!("/proc/auxtools_new_gas_mixture"))
+		.ok_or_else(|| runtime!("/proc/auxtools_new_gas_mixture must exist to make gas mixtures"))?
+		.call(&[])
+}
+
+/// Gets the mix for the given value, and calls the provided closure with a reference to that mix as an argument.
+/// # Errors
+/// If a gasmixture ID is not a number or the callback returns an error.
+pub fn with_mix<T, F>(mix: &Value, f: F) -> Result<T, Runtime>
+where
+	F: FnMut(&Mixture) -> Result<T, Runtime>,
+{
+	GasArena::with_gas_mixture(mix_index(mix)?, f)
+}
+
+/// As `with_mix`, but also passes the mix's index in the arena to the closure, for anything keyed by it.
+/// # Errors
+/// If a gasmixture ID is not a number or the callback returns an error.
+pub fn with_mix_indexed<T, F>(mix: &Value, mut f: F) -> Result<T, Runtime>
+where
+	F: FnMut(usize, &Mixture) -> Result<T, Runtime>,
+{
+	let id = mix_index(mix)?;
+	GasArena::with_gas_mixture(id, |mixture| f(id, mixture))
 }
 
 /// As `with_mix`, but mutable.
@@ -351,19 +560,7 @@ pub fn with_mix_mut<T, F>(mix: &Value, f: F) -> Result<T, Runtime>
 where
 	F: FnMut(&mut Mixture) -> Result<T, Runtime>,
 {
-	GasArena::with_gas_mixture_mut(
-		mix.get_number(byond_string!("_extools_pointer_gasmixture"))
-			.map_err(|_| {
-				runtime!(
-					"Attempt to interpret non-number value as number {} {}:{}",
-					std::file!(),
-					std::line!(),
-					std::column!()
-				)
-			})?
-			.to_bits() as usize,
-		f,
-	)
+	GasArena::with_gas_mixture_mut(mix_index(mix)?, f)
 }
 
 /// As `with_mix`, but with two mixes.
@@ -373,31 +570,7 @@ pub fn with_mixes<T, F>(src_mix: &Value, arg_mix: &Value, f: F) -> Result<T, Run
 where
 	F: FnMut(&Mixture, &Mixture) -> Result<T, Runtime>,
 {
-	GasArena::with_gas_mixtures(
-		src_mix
-			.get_number(byond_string!("_extools_pointer_gasmixture"))
-			.map_err(|_| {
-				runtime!(
-					"Attempt to interpret non-number value as number {} {}:{}",
-					std::file!(),
-					std::line!(),
-					std::column!()
-				)
-			})?
-			.to_bits() as usize,
-		arg_mix
-			.get_number(byond_string!("_extools_pointer_gasmixture"))
-			.map_err(|_| {
-				runtime!(
-					"Attempt to interpret non-number value as number {} {}:{}",
-					std::file!(),
-					std::line!(),
-					std::column!()
-				)
-			})?
-			.to_bits() as usize,
-		f,
-	)
+	GasArena::with_gas_mixtures(mix_index(src_mix)?, mix_index(arg_mix)?, f)
 }
 
 /// As `with_mix_mut`, but with two mixes.
@@ -407,31 +580,7 @@ pub fn with_mixes_mut<T, F>(src_mix: &Value, arg_mix: &Value, f: F) -> Result<T,
 where
 	F: FnMut(&mut Mixture, &mut Mixture) -> Result<T, Runtime>,
 {
-	GasArena::with_gas_mixtures_mut(
-		src_mix
-			.get_number(byond_string!("_extools_pointer_gasmixture"))
-			.map_err(|_| {
-				runtime!(
-					"Attempt to interpret non-number value as number {} {}:{}",
-					std::file!(),
-					std::line!(),
-					std::column!()
-				)
-			})?
-			.to_bits() as usize,
-		arg_mix
-			.get_number(byond_string!("_extools_pointer_gasmixture"))
-			.map_err(|_| {
-				runtime!(
-					"Attempt to interpret non-number value as number {} {}:{}",
-					std::file!(),
-					std::line!(),
-					std::column!()
-				)
-			})?
-			.to_bits() as usize,
-		f,
-	)
+	GasArena::with_gas_mixtures_mut(mix_index(src_mix)?, mix_index(arg_mix)?, f)
 }
 
 /// Allows different lock levels for each gas. Instead of relevant refs to the gases, returns the `RWLock` object.
@@ -441,31 +590,7 @@ pub fn with_mixes_custom<T, F>(src_mix: &Value, arg_mix: &Value, f: F) -> Result
 where
 	F: FnMut(&RwLock<Mixture>, &RwLock<Mixture>) -> Result<T, Runtime>,
 {
-	GasArena::with_gas_mixtures_custom(
-		src_mix
-			.get_number(byond_string!("_extools_pointer_gasmixture"))
-			.map_err(|_| {
-				runtime!(
-					"Attempt to interpret non-number value as number {} {}:{}",
-					std::file!(),
-					std::line!(),
-					std::column!()
-				)
-			})?
-			.to_bits() as usize,
-		arg_mix
-			.get_number(byond_string!("_extools_pointer_gasmixture"))
-			.map_err(|_| {
-				runtime!(
-					"Attempt to interpret non-number value as number {} {}:{}",
-					std::file!(),
-					std::line!(),
-					std::column!()
-				)
-			})?
-			.to_bits() as usize,
-		f,
-	)
+	GasArena::with_gas_mixtures_custom(mix_index(src_mix)?, mix_index(arg_mix)?, f)
 }
 
 pub fn amt_gases() -> usize {
@@ -475,3 +600,157 @@ pub fn amt_gases() -> usize {
 pub fn tot_gases() -> usize {
 	GAS_MIXTURES.read().as_ref().unwrap().len()
 }
+
+/// Records the current number of gas mixtures in use for `gas_count_trend`, and returns it.
+/// # Panics
+/// If not called from the main thread.
+pub fn sample_gas_count() -> usize {
+	let count = amt_gases();
+	GAS_COUNT_SAMPLES.with(|samples| {
+		let mut samples = samples.borrow_mut();
+		if samples.len() >= MAX_GAS_COUNT_SAMPLES {
+			samples.pop_front();
+		}
+		samples.push_back(count);
+	});
+	count
+}
+
+/// The slope of the least-squares line through the recent `sample_gas_count` samples, in mixtures per sample.
+/// Consistently positive when nothing much should be changing means mixtures are being made and never deleted.
+/// 0 with fewer than two samples.
+/// # Panics
+/// If not called from the main thread.
+pub fn gas_count_trend() -> f32 {
+	GAS_COUNT_SAMPLES.with(|samples| {
+		let samples = samples.borrow();
+		let n = samples.len() as f64;
+		if samples.len() < 2 {
+			return 0.0;
+		}
+		let mean_x = (n - 1.0) / 2.0;
+		let mean_y = samples.iter().map(|&count| count as f64).sum::<f64>() / n;
+		let (covariance, variance) =
+			samples
+				.iter()
+				.enumerate()
+				.fold((0.0, 0.0), |(cov, var), (x, &count)| {
+					let dx = x as f64 - mean_x;
+					(cov + dx * (count as f64 - mean_y), var + dx * dx)
+				});
+		(covariance / variance) as f32
+	})
+}
+
+/// The arena indices that aren't in use by any gas mixture.
+fn free_gas_ids() -> HashSet<usize, FxBuildHasher> {
+	NEXT_GAS_IDS
+		.read()
+		.as_ref()
+		.unwrap()
+		.iter()
+		.copied()
+		.collect()
+}
+
+/// Checks every live gas mixture, in parallel, returning the arena index and corruption reason of each corrupt one.
+pub fn validate_all_gas_mixtures() -> Vec<(usize, &'static str)> {
+	use rayon::prelude::*;
+	let free_ids = free_gas_ids();
+	GasArena::with_all_mixtures(|mixtures| {
+		mixtures
+			.par_iter()
+			.enumerate()
+			.filter(|(i, _)| !free_ids.contains(i))
+			.filter_map(|(i, mix)| mix.read().corruption_reason().map(|reason| (i, reason)))
+			.collect()
+	})
+}
+
+/// The total thermal energy of every live gas mixture, summed in parallel.
+pub fn global_thermal_energy() -> f64 {
+	use rayon::prelude::*;
+	let free_ids = free_gas_ids();
+	GasArena::with_all_mixtures(|mixtures| {
+		mixtures
+			.par_iter()
+			.enumerate()
+			.filter(|(i, _)| !free_ids.contains(i))
+			.map(|(_, mix)| f64::from(mix.read().thermal_energy()))
+			.sum()
+	})
+}
+
+/// Packs the state of every live gas mixture into one flat, struct-of-arrays blob, in a single pass over the arena.
+/// Everything is little-endian; `N` is the number of live mixtures and `E` the number of nonzero gas entries:
+/// - `u32` N, then `u32` the total number of gas types
+/// - N × `u32` arena index of each mixture
+/// - N × `f32` temperature of each mixture, in kelvins
+/// - N × `f32` volume of each mixture, in liters
+/// - `u32` E
+/// - E × `u32` which mixture each entry belongs to, as a position in the arrays above
+/// - E × `u32` gas index of each entry
+/// - E × `f32` moles of each entry
+///
+/// Entries are grouped by mixture, in the same order as the mixtures.
+pub fn export_all_gas_state() -> Vec<u8> {
+	let free_ids = free_gas_ids();
+	let mut indices: Vec<u32> = Vec::new();
+	let mut temperatures: Vec<f32> = Vec::new();
+	let mut volumes: Vec<f32> = Vec::new();
+	let mut entry_mixtures: Vec<u32> = Vec::new();
+	let mut entry_gases: Vec<u32> = Vec::new();
+	let mut entry_moles: Vec<f32> = Vec::new();
+	GasArena::with_all_mixtures(|mixtures| {
+		for (i, mix) in mixtures.iter().enumerate() {
+			if free_ids.contains(&i) {
+				continue;
+			}
+			let mix = mix.read();
+			let position = indices.len() as u32;
+			indices.push(i as u32);
+			temperatures.push(mix.get_temperature());
+			volumes.push(mix.volume);
+			for (gas, moles) in mix.enumerate().filter(|&(_, moles)| moles != 0.0) {
+				entry_mixtures.push(position);
+				entry_gases.push(gas as u32);
+				entry_moles.push(moles);
+			}
+		}
+	});
+	let mut blob = Vec::with_capacity(4 * (3 + 3 * indices.len() + 3 * entry_moles.len()));
+	blob.extend_from_slice(&(indices.len() as u32).to_le_bytes());
+	blob.extend_from_slice(&(total_num_gases() as u32).to_le_bytes());
+	indices
+		.iter()
+		.for_each(|idx| blob.extend_from_slice(&idx.to_le_bytes()));
+	temperatures
+		.iter()
+		.for_each(|temp| blob.extend_from_slice(&temp.to_le_bytes()));
+	volumes
+		.iter()
+		.for_each(|vol| blob.extend_from_slice(&vol.to_le_bytes()));
+	blob.extend_from_slice(&(entry_moles.len() as u32).to_le_bytes());
+	entry_mixtures
+		.iter()
+		.for_each(|pos| blob.extend_from_slice(&pos.to_le_bytes()));
+	entry_gases
+		.iter()
+		.for_each(|gas| blob.extend_from_slice(&gas.to_le_bytes()));
+	entry_moles
+		.iter()
+		.for_each(|moles| blob.extend_from_slice(&moles.to_le_bytes()));
+	blob
+}
+
+/// A rough estimate, in bytes, of the memory used by the gas mixture arena, including free slots and spilled mole vectors.
+pub fn gas_memory_estimate() -> usize {
+	let mixtures = GAS_MIXTURES.read();
+	let mixtures = mixtures.as_ref().unwrap();
+	mixtures.capacity() * std::mem::size_of::<RwLock<Mixture>>()
+		+ NEXT_GAS_IDS.read().as_ref().unwrap().capacity() * std::mem::size_of::<usize>()
+		+ mixtures
+			.iter()
+			.map(|mix| mix.read().heap_size())
+			.sum::<usize>()
+}
@@ -16,6 +16,12 @@ pub const T20C: f32 = 293.15;
 pub const GAS_MIN_MOLES: f32 = 0.0001;
 /// Heat capacities below which heat will be considered 0.
 pub const MINIMUM_HEAT_CAPACITY: f32 = 0.0003;
+/// The fastest sound can go through a mix, in meters per second, so gases with little or no molar mass don't give absurd or infinite speeds.
+pub const MAX_SPEED_OF_SOUND: f32 = 20_000.0;
+/// How finely `Mixture::spectral_signature` bins mole fractions: 20 bins is 5% each.
+pub const SIGNATURE_BINS: u32 = 20;
+/// The format version `Mixture::serialize` writes first. Bump it when the format changes, and migrate the older versions in `Mixture::deserialize`.
+pub const SERIALIZATION_VERSION: u8 = 1;
 
 /// liters in a cell
 pub const CELL_VOLUME: f32 = 2500.0;
@@ -76,6 +82,9 @@ pub const WALL_HEAT_TRANSFER_COEFFICIENT: f32 = 0.0;
 pub const OPEN_HEAT_TRANSFER_COEFFICIENT: f32 = 0.4;
 /// a hack for now
 pub const WINDOW_HEAT_TRANSFER_COEFFICIENT: f32 = 0.1;
+/// The highest conduction coefficient temperature sharing will use. At 1, both mixes end up at the same temperature;
+/// anything higher overshoots, leaving the hotter mix colder than the one it shared with.
+pub const MAX_CONDUCTION_COEFFICIENT: f32 = 1.0;
 /// a hack to help make vacuums "cold", sacrificing realism for gameplay
 pub const HEAT_CAPACITY_VACUUM: f32 = 7000.0;
 
@@ -127,6 +136,8 @@ bitflags! {
 		const NO_REACTION = 0b0;
 		const REACTING = 0b1;
 		const STOP_REACTIONS = 0b10;
+		/// Returned by react, without running any reactions, on a mixture that's already reacting.
+		const REACTION_BUSY = 0b100;
 	}
 }
 
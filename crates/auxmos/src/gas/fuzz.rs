@@ -0,0 +1,49 @@
+//! Debug helpers for stress-testing the simulation with random, but reproducible, gas mixtures.
+//! Not meant for production builds; only compiled with the `fuzz_helpers` feature.
+
+use auxtools::*;
+
+use super::{constants::*, new_gas_mixture, total_num_gases, with_mix_mut, Mixture};
+
+use crate::rng::SplitMix64;
+
+/// Highest amount of any one gas a random mixture will get.
+const MAX_RANDOM_MOLES: f32 = 1000.0;
+
+/// Highest temperature a random mixture will get.
+const MAX_RANDOM_TEMPERATURE: f32 = 10000.0;
+
+/// Clears the given mix and fills it with up to `max_gases` random gases at a random temperature.
+pub fn randomize_mixture(mix: &mut Mixture, max_gases: usize, rng: &mut SplitMix64) {
+	mix.clear();
+	let num_gases = total_num_gases();
+	let max_gases = max_gases.min(num_gases);
+	if max_gases == 0 {
+		return;
+	}
+	for _ in 0..=rng.next_below(max_gases) {
+		let idx = rng.next_below(num_gases);
+		mix.set_moles(idx, rng.next_f32() * MAX_RANDOM_MOLES);
+	}
+	mix.set_temperature(TCMB + rng.next_f32() * (MAX_RANDOM_TEMPERATURE - TCMB));
+}
+
+/// Args: (count, max_gases, seed). Makes `count` new gas mixtures with random gases and temperatures, the same ones for the same seed.
+/// Requires `/proc/auxtools_new_gas_mixture`, which should return a new `/datum/gas_mixture`.
+/// Returns: a list of the new mixtures.
+#[hook("/proc/spawn_random_gas_mixtures")]
+fn _spawn_random_gas_mixtures_hook(count: Value, max_gases: Value, seed: Value) {
+	let count = count.as_number()? as u32;
+	let max_gases = max_gases.as_number()? as usize;
+	let mut rng = SplitMix64::new(seed.as_number()?.to_bits() as u64);
+	let mixes: List = List::new();
+	for _ in 0..count {
+		let mix_val = new_gas_mixture()?;
+		with_mix_mut(&mix_val, |mix| {
+			randomize_mixture(mix, max_gases, &mut rng);
+			Ok(())
+		})?;
+		mixes.append(mix_val);
+	}
+	Ok(Value::from(mixes))
+}
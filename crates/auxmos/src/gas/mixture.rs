@@ -10,13 +10,20 @@ use tinyvec::TinyVec;
 
 use crate::reaction::{Reaction, ReactionPriority};
 
+#[cfg(feature = "variable_specific_heat")]
+use super::{evaluate_specific_heat, with_specific_heat_polynomials};
+
+#[cfg(not(feature = "variable_specific_heat"))]
+use super::with_specific_heats;
+
 use super::{
-	constants::*, gas_visibility, total_num_gases, with_reactions, with_specific_heats, GasIDX,
+	constants::*, gas_visibility, minimum_moles_delta, reaction_generation, total_num_gases,
+	with_gas_info, with_reactions, GasIDX, GasVisibility,
 };
 
 use std::sync::atomic::{AtomicU64, Ordering::Relaxed};
 
-use std::collections::BTreeMap;
+use std::{cell::RefCell, collections::BTreeMap};
 
 type SpecificFireInfo = (usize, f32, f32);
 
@@ -49,11 +56,26 @@ impl GasCache {
 			Err(x) => x,
 		}
 	}
+	/// The cached value, if it's valid.
+	pub fn peek(&self) -> Option<f32> {
+		Some(self.0.load(Relaxed)).filter(|v| !v.is_nan())
+	}
+	#[cfg_attr(feature = "variable_specific_heat", allow(dead_code))]
 	pub fn set(&self, v: f32) {
 		self.0.store(v, Relaxed);
 	}
 }
 
+/// The state hash of the last state a mix was found to have no possible reactions in, or 0 if none.
+#[derive(Default)]
+struct NoReactionCache(AtomicU64);
+
+impl Clone for NoReactionCache {
+	fn clone(&self) -> Self {
+		Self(AtomicU64::new(self.0.load(Relaxed)))
+	}
+}
+
 pub fn visibility_step(gas_amt: f32) -> u32 {
 	(gas_amt / MOLES_GAS_VISIBLE_STEP)
 		.ceil()
@@ -61,6 +83,19 @@ pub fn visibility_step(gas_amt: f32) -> u32 {
 		.max(1.0) as u32
 }
 
+/// What a call to `Mixture::set_moles_report` did to the gas it was given.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SetMolesResult {
+	/// The gas wasn't present before, and now is.
+	Added,
+	/// The gas was present before and still is, but the amount changed.
+	Updated,
+	/// The gas was present before, and now isn't.
+	Removed,
+	/// Nothing changed, either because the amount was the same or because the set was rejected.
+	NoChange,
+}
+
 /// The data structure representing a Space Station 13 gas mixture.
 /// Unlike Monstermos, this doesn't have the archive built-in; instead,
 /// the archive is a feature of the turf grid, only existing during
@@ -72,10 +107,19 @@ pub fn visibility_step(gas_amt: f32) -> u32 {
 #[derive(Clone)]
 pub struct Mixture {
 	temperature: f32,
+	/// Liters.
 	pub volume: f32,
 	min_heat_capacity: f32,
 	moles: TinyVec<[f32; 8]>,
+	/// Invariant: if valid (not NaN), equals `slow_heat_capacity()`. Anything that changes moles or
+	/// `min_heat_capacity` must invalidate it; temperature changes only need to when heat capacity
+	/// depends on temperature, i.e. with `variable_specific_heat`, which `set_temperature` handles.
 	cached_heat_capacity: GasCache,
+	/// Invariant: if valid, equals the sum of `moles`. Anything that changes moles must invalidate it,
+	/// which `invalidate_mole_caches` does along with `cached_heat_capacity`.
+	cached_total_moles: GasCache,
+	/// Keyed by `reaction_state_hash`, so any change to the mix, or to the reactions, invalidates it by itself.
+	no_reaction_cache: NoReactionCache,
 	immutable: bool,
 }
 
@@ -96,7 +140,19 @@ impl Mixture {
 			min_heat_capacity: 0.0,
 			immutable: false,
 			cached_heat_capacity: GasCache::default(),
+			cached_total_moles: GasCache::default(),
+			no_reaction_cache: NoReactionCache::default(),
+		}
+	}
+	/// Makes a gas mixture with the given gases, temperature and volume.
+	#[must_use]
+	pub fn from_gas_amounts(amounts: &[(GasIDX, f32)], temperature: f32, volume: f32) -> Self {
+		let mut ret = Self::from_vol(volume);
+		for &(idx, amt) in amounts {
+			ret.set_moles(idx, amt);
 		}
+		ret.set_temperature(temperature);
+		ret
 	}
 	/// Makes an empty gas mixture with the given volume.
 	#[must_use]
@@ -107,11 +163,43 @@ impl Mixture {
 	}
 	/// Returns if any data is corrupt.
 	pub fn is_corrupt(&self) -> bool {
-		!self.temperature.is_normal() || self.moles.len() > total_num_gases()
+		!self.temperature.is_normal() || !self.validate_indices_against_gas_table()
+	}
+	/// Returns whether every gas in the mix has an index that exists in the current gas table.
+	/// Trailing zeroed entries past the end of the table are fine; gases actually present there are not.
+	pub fn validate_indices_against_gas_table(&self) -> bool {
+		let num_gases = total_num_gases();
+		self.moles.len() <= num_gases || self.moles[num_gases..].iter().all(|&amt| amt == 0.0)
+	}
+	/// Returns why this mix is corrupt, or `None` if it isn't. Checks more than `is_corrupt`, so slower.
+	pub fn corruption_reason(&self) -> Option<&'static str> {
+		if !self.temperature.is_normal() {
+			Some("temperature is not a normal number")
+		} else if !self.validate_indices_against_gas_table() {
+			Some("has gases past the end of the gas table")
+		} else if self.moles.iter().any(|amt| amt.is_nan()) {
+			Some("moles are NaN")
+		} else if self.moles.iter().any(|&amt| amt < 0.0) {
+			Some("moles are negative")
+		} else if self.cached_heat_capacity.peek().map_or(false, |cached| {
+			let actual = self.slow_heat_capacity();
+			(cached - actual).abs() > actual.abs().max(1.0) * 0.001
+		}) {
+			Some("cached heat capacity doesn't match the moles")
+		} else if self.cached_total_moles.peek().map_or(false, |cached| {
+			let actual: f32 = self.moles.iter().sum();
+			(cached - actual).abs() > actual.abs().max(1.0) * 0.001
+		}) {
+			Some("cached total moles don't match the moles")
+		} else {
+			None
+		}
 	}
 	/// Fixes any corruption found.
 	pub fn fix_corruption(&mut self) {
+		self.moles.truncate(total_num_gases());
 		self.garbage_collect();
+		self.invalidate_mole_caches();
 		if self.temperature < 2.7 || !self.temperature.is_normal() {
 			self.set_temperature(293.15);
 		}
@@ -120,20 +208,68 @@ impl Mixture {
 	pub fn get_temperature(&self) -> f32 {
 		self.temperature
 	}
+	/// The temperature, cleaned up for showing to players: never below TCMB, with denormals read as TCMB.
+	/// Only for display; physics should use `get_temperature`.
+	pub fn get_temperature_display(&self) -> f32 {
+		if self.temperature.is_normal() {
+			self.temperature.max(TCMB)
+		} else {
+			TCMB
+		}
+	}
 	/// Sets the temperature, if the mix isn't immutable. T
 	pub fn set_temperature(&mut self, temp: f32) {
 		if !self.immutable && temp.is_normal() {
 			self.temperature = temp;
+			#[cfg(feature = "variable_specific_heat")]
+			self.cached_heat_capacity.invalidate();
 		}
 	}
+	/// Clamps the temperature between `min` and `max`, if the mix isn't immutable. `min` is never taken as lower than TCMB,
+	/// and if `max` is lower than `min`, `min` wins. Returns the temperature afterwards.
+	pub fn clamp_temperature(&mut self, min: f32, max: f32) -> f32 {
+		let min = min.max(TCMB);
+		self.set_temperature(self.temperature.min(max).max(min));
+		self.temperature
+	}
 	/// Sets the minimum heat capacity of this mix.
+	/// `heat_capacity` never returns less than this, even for an empty mix, so it also sets
+	/// how much heat it takes to change an empty mix's temperature in `adjust_heat` and `thermal_energy`.
 	pub fn set_min_heat_capacity(&mut self, amt: f32) {
 		self.min_heat_capacity = amt;
+		self.cached_heat_capacity.invalidate();
+	}
+	/// Returns the minimum heat capacity of this mix.
+	pub fn get_min_heat_capacity(&self) -> f32 {
+		self.min_heat_capacity
 	}
 	/// Returns an iterator over the gas keys and mole amounts thereof.
 	pub fn enumerate(&self) -> impl Iterator<Item = (GasIDX, f32)> + '_ {
 		self.moles.iter().copied().enumerate()
 	}
+	/// Bytes this mix has allocated on the heap, i.e. for its moles if there are too many gases to fit inline.
+	pub fn heap_size(&self) -> usize {
+		if self.moles.is_heap() {
+			self.moles.capacity() * std::mem::size_of::<f32>()
+		} else {
+			0
+		}
+	}
+	/// Returns a bitfield of which gases are present in this mix, bit `n` being gas index `n`.
+	/// Gases with an index of 64 or above aren't represented.
+	pub fn gases_bitfield(&self) -> u64 {
+		self.enumerate()
+			.filter(|&(i, amt)| i < 64 && amt > 0.0)
+			.fold(0, |acc, (i, _)| acc | (1 << i))
+	}
+	/// Whether this mix and another have any gas in common.
+	pub fn shares_gas_with(&self, other: &Self) -> bool {
+		self.gases_bitfield() & other.gases_bitfield() != 0
+	}
+	/// As `enumerate`, but only the gases with more than `threshold` moles.
+	pub fn gases_above(&self, threshold: f32) -> impl Iterator<Item = (GasIDX, f32)> + '_ {
+		self.enumerate().filter(move |&(_, amt)| amt > threshold)
+	}
 	/// Allows closures to iterate over each gas.
 	/// # Errors
 	/// If the closure errors.
@@ -153,15 +289,134 @@ impl Mixture {
 		&mut self,
 		mut f: impl FnMut(GasIDX, &mut f32) -> Result<(), auxtools::Runtime>,
 	) -> Result<(), auxtools::Runtime> {
-		for (i, g) in self.moles.iter_mut().enumerate() {
-			f(i, g)?;
-		}
-		Ok(())
+		let result = self
+			.moles
+			.iter_mut()
+			.enumerate()
+			.try_for_each(|(i, g)| f(i, g));
+		self.invalidate_mole_caches();
+		result
 	}
 	/// Returns (by value) the amount of moles of a given index the mix has. M
 	pub fn get_moles(&self, idx: GasIDX) -> f32 {
 		self.moles.get(idx).copied().unwrap_or(0.0)
 	}
+	/// Returns whether the mix has more than `GAS_MIN_MOLES` of the given gas.
+	pub fn gas_present(&self, idx: GasIDX) -> bool {
+		self.moles
+			.get(idx)
+			.map_or(false, |&amt| amt > GAS_MIN_MOLES)
+	}
+	/// The partial pressure of the given gas, in kilopascals; 0 if there's none of it, or the mix has no volume.
+	pub fn partial_pressure(&self, idx: GasIDX) -> f32 {
+		if self.volume > 0.0 {
+			self.get_moles(idx) * R_IDEAL_GAS_EQUATION * self.temperature / self.volume
+		} else {
+			0.0
+		}
+	}
+	/// The partial pressure of every gas present, as in `gas_present`, in kilopascals.
+	pub fn partial_pressures(&self) -> impl Iterator<Item = (GasIDX, f32)> + '_ {
+		self.enumerate()
+			.filter(|&(_, amt)| amt > GAS_MIN_MOLES)
+			.map(move |(i, _)| (i, self.partial_pressure(i)))
+	}
+	/// The fraction of the mix's moles that are the given gas, from 0 to 1; 0 if the mix is empty.
+	pub fn mole_fraction(&self, idx: GasIDX) -> f32 {
+		let total_moles = self.total_moles();
+		if total_moles > 0.0 {
+			self.get_moles(idx) / total_moles
+		} else {
+			0.0
+		}
+	}
+	/// The gas the mix has the most moles of, or `None` if it has no gas present, as in `gas_present`.
+	/// Ties go to the lowest index.
+	pub fn dominant_gas(&self) -> Option<GasIDX> {
+		self.enumerate()
+			.fold(None, |best: Option<(GasIDX, f32)>, (idx, amt)| match best {
+				Some((_, most)) if most >= amt => best,
+				_ if amt > GAS_MIN_MOLES => Some((idx, amt)),
+				_ => best,
+			})
+			.map(|(idx, _)| idx)
+	}
+	/// Every gas present, as in `gas_present`, with its moles, from the most to the least. Equal amounts stay in index order.
+	pub fn gases_by_amount(&self) -> Vec<(GasIDX, f32)> {
+		let mut gases = self
+			.enumerate()
+			.filter(|&(_, amt)| amt > GAS_MIN_MOLES)
+			.collect::<Vec<_>>();
+		gases.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+		gases
+	}
+	/// The total mass of the gas in the mix, in grams, going by each gas's `molar_mass`.
+	fn mass(&self) -> f32 {
+		with_gas_info(|gases| {
+			self.enumerate()
+				.map(|(idx, amt)| amt * gases.get(idx).map_or(0.0, |gas| gas.molar_mass))
+				.sum()
+		})
+	}
+	/// The mole-weighted average molar mass of the gases in the mix, in grams per mole; 0 if the mix is empty.
+	pub fn mean_molar_mass(&self) -> f32 {
+		let total_moles = self.total_moles();
+		if total_moles > 0.0 {
+			self.mass() / total_moles
+		} else {
+			0.0
+		}
+	}
+	/// The density of the mix, in grams per liter (i.e. kilograms per cubic meter); 0 if it's empty or has no volume.
+	pub fn density(&self) -> f32 {
+		if self.volume > 0.0 {
+			self.mass() / self.volume
+		} else {
+			0.0
+		}
+	}
+	/// The speed of sound in the mix, in meters per second: sqrt(γRT/M), with γ worked out from each gas's `adiabatic_index`
+	/// through the mix's molar heat capacities at constant volume and pressure.
+	/// 0 in a vacuum, and at most `MAX_SPEED_OF_SOUND`, which is also what weightless gases get.
+	pub fn speed_of_sound(&self) -> f32 {
+		let total_moles = self.total_moles();
+		if total_moles <= 0.0 {
+			return 0.0;
+		}
+		let (mass, constant_volume_capacity) = with_gas_info(|gases| {
+			self.enumerate()
+				.fold((0.0, 0.0), |(mass, capacity), (idx, amt)| {
+					gases.get(idx).map_or((mass, capacity), |gas| {
+						(
+							mass + amt * gas.molar_mass,
+							capacity + amt / (gas.adiabatic_index - 1.0),
+						)
+					})
+				})
+		});
+		// both in units of R; the constant pressure capacity is always R more per mole
+		let adiabatic_index = (constant_volume_capacity + total_moles) / constant_volume_capacity;
+		// grams per mole to kilograms per mole
+		let molar_mass = mass / total_moles / 1000.0;
+		if molar_mass <= 0.0 {
+			return MAX_SPEED_OF_SOUND;
+		}
+		(adiabatic_index * R_IDEAL_GAS_EQUATION * self.temperature / molar_mass)
+			.sqrt()
+			.min(MAX_SPEED_OF_SOUND)
+	}
+	/// The ratio of the moles of gas `a` to the moles of gas `b`, e.g. fuel to oxidizer.
+	/// Infinite if there's none of `b`, or NaN if there's none of either.
+	pub fn mole_ratio(&self, a: GasIDX, b: GasIDX) -> f32 {
+		let denominator = self.get_moles(b);
+		if denominator > 0.0 {
+			self.get_moles(a) / denominator
+		} else if self.get_moles(a) > 0.0 {
+			f32::INFINITY
+		} else {
+			f32::NAN
+		}
+	}
 	/// Sets the mix to be internally immutable. Rust doesn't know about any of this, obviously.
 	pub fn mark_immutable(&mut self) {
 		self.immutable = true;
@@ -185,7 +440,70 @@ impl Mixture {
 			unsafe {
 				*self.moles.get_unchecked_mut(idx) = amt;
 			};
-			self.cached_heat_capacity.invalidate();
+			self.invalidate_mole_caches();
+		}
+	}
+	/// As `set_moles`, but errors instead of quietly doing nothing (or worse) if `amt` is NaN, infinite or negative, or there's no gas at `idx`.
+	/// # Errors
+	/// If `amt` isn't a finite, non-negative number, or `idx` isn't a valid gas index.
+	pub fn try_set_moles(&mut self, idx: GasIDX, amt: f32) -> Result<(), auxtools::Runtime> {
+		if !amt.is_finite() {
+			return Err(auxtools::runtime!(
+				"Attempted to set moles to NaN or infinity."
+			));
+		}
+		if amt < 0.0 {
+			return Err(auxtools::runtime!(
+				"Attempted to set moles to a negative number."
+			));
+		}
+		if idx >= total_num_gases() {
+			return Err(auxtools::runtime!("Invalid gas index: {}", idx));
+		}
+		self.set_moles(idx, amt);
+		Ok(())
+	}
+	/// Sets the moles of every given gas at once, expanding and invalidating caches only once.
+	/// Every amount is checked as in `try_set_moles` before anything is set, so on an error the mix is left untouched.
+	/// # Errors
+	/// If any amount isn't a finite, non-negative number, or any index isn't a valid gas index.
+	pub fn try_set_multi(&mut self, amounts: &[(GasIDX, f32)]) -> Result<(), auxtools::Runtime> {
+		let num_gases = total_num_gases();
+		for &(idx, amt) in amounts {
+			if !amt.is_finite() || amt < 0.0 {
+				return Err(auxtools::runtime!(
+					"Attempted to set moles of gas {} to {}, which isn't a finite, non-negative number.",
+					idx,
+					amt
+				));
+			}
+			if idx >= num_gases {
+				return Err(auxtools::runtime!("Invalid gas index: {}", idx));
+			}
+		}
+		if self.immutable || amounts.is_empty() {
+			return Ok(());
+		}
+		self.maybe_expand(amounts.iter().map(|&(idx, _)| idx).max().unwrap_or(0) + 1);
+		for &(idx, amt) in amounts {
+			self.moles[idx] = amt;
+		}
+		self.invalidate_mole_caches();
+		self.garbage_collect();
+		Ok(())
+	}
+	/// As `set_moles`, but reports whether the gas was added, updated or removed.
+	/// A gas counts as present if it has more than `GAS_MIN_MOLES` moles.
+	pub fn set_moles_report(&mut self, idx: GasIDX, amt: f32) -> SetMolesResult {
+		let old = self.get_moles(idx);
+		self.set_moles(idx, amt);
+		let new = self.get_moles(idx);
+		match (old > GAS_MIN_MOLES, new > GAS_MIN_MOLES) {
+			_ if old.to_bits() == new.to_bits() => SetMolesResult::NoChange,
+			(false, true) => SetMolesResult::Added,
+			(true, false) => SetMolesResult::Removed,
+			(true, true) => SetMolesResult::Updated,
+			(false, false) => SetMolesResult::NoChange,
 		}
 	}
 	pub fn adjust_moles(&mut self, idx: GasIDX, amt: f32) {
@@ -196,7 +514,7 @@ impl Mixture {
 			if amt <= 0.0 {
 				self.garbage_collect();
 			}
-			self.cached_heat_capacity.invalidate();
+			self.invalidate_mole_caches();
 		}
 	}
 	pub fn adjust_multi(&mut self, adjustments: &[(usize, f32)]) {
@@ -222,13 +540,14 @@ impl Mixture {
 				}
 			}
 			if dirty {
-				self.cached_heat_capacity.invalidate();
+				self.invalidate_mole_caches();
 			}
 			if should_collect {
 				self.garbage_collect();
 			}
 		}
 	}
+	#[cfg(not(feature = "variable_specific_heat"))]
 	#[inline(never)] // mostly this makes it so that heat_capacity itself is inlined
 	fn slow_heat_capacity(&self) -> f32 {
 		with_specific_heats(|heats| {
@@ -240,6 +559,20 @@ impl Mixture {
 		})
 		.max(self.min_heat_capacity)
 	}
+	#[cfg(feature = "variable_specific_heat")]
+	#[inline(never)]
+	fn slow_heat_capacity(&self) -> f32 {
+		with_specific_heat_polynomials(|polys| {
+			self.moles
+				.iter()
+				.copied()
+				.zip(polys.iter())
+				.fold(0.0, |acc, (amt, poly)| {
+					evaluate_specific_heat(poly, self.temperature).mul_add(amt, acc)
+				})
+		})
+		.max(self.min_heat_capacity)
+	}
 	/// The heat capacity of the material. [joules?]/mole-kelvin.
 	pub fn heat_capacity(&self) -> f32 {
 		self.cached_heat_capacity
@@ -250,16 +583,59 @@ impl Mixture {
 		self.moles
 			.get(idx)
 			.filter(|amt| amt.is_normal())
-			.map_or(0.0, |amt| amt * with_specific_heats(|heats| heats[idx]))
+			.map_or(0.0, |amt| amt * self.specific_heat_of(idx))
+	}
+	#[cfg(not(feature = "variable_specific_heat"))]
+	fn specific_heat_of(&self, idx: GasIDX) -> f32 {
+		with_specific_heats(|heats| heats[idx])
+	}
+	#[cfg(feature = "variable_specific_heat")]
+	fn specific_heat_of(&self, idx: GasIDX) -> f32 {
+		with_specific_heat_polynomials(|polys| {
+			evaluate_specific_heat(&polys[idx], self.temperature)
+		})
 	}
 	/// The total mole count of the mixture. Moles.
 	pub fn total_moles(&self) -> f32 {
-		self.moles.iter().sum()
+		self.cached_total_moles
+			.get_or_else(|| self.moles.iter().sum())
+	}
+	/// How many different gases are in the mix, not counting any at or below `GAS_MIN_MOLES`.
+	pub fn gas_count(&self) -> usize {
+		self.moles
+			.iter()
+			.filter(|&&amt| amt > GAS_MIN_MOLES)
+			.count()
 	}
 	/// Pressure. Kilopascals.
 	pub fn return_pressure(&self) -> f32 {
 		self.total_moles() * R_IDEAL_GAS_EQUATION * self.temperature / self.volume
 	}
+	/// Moles per liter. Zero if the mix has no volume.
+	pub fn moles_per_liter(&self) -> f32 {
+		if self.volume > 0.0 {
+			self.total_moles() / self.volume
+		} else {
+			0.0
+		}
+	}
+	/// The volume this mix's gas would take up at standard temperature and pressure (0 C, 1 atm), in liters.
+	/// Moles don't depend on temperature, so this is just a more intuitive unit for comparing mixes of different temperatures and volumes.
+	pub fn volume_at_stp(&self) -> f32 {
+		let total_moles = self.total_moles();
+		if total_moles > 0.0 {
+			total_moles * R_IDEAL_GAS_EQUATION * T0C / ONE_ATMOSPHERE
+		} else {
+			0.0
+		}
+	}
+	/// How many more moles this mix can take, at its current temperature, before its pressure reaches `max_pressure`.
+	/// Zero, never negative, if the mix is already at or above that pressure.
+	pub fn moles_until_pressure(&self, max_pressure: f32) -> f32 {
+		(max_pressure * self.volume / (R_IDEAL_GAS_EQUATION * self.temperature)
+			- self.total_moles())
+		.max(0.0)
+	}
 	/// Thermal energy. Joules?
 	pub fn thermal_energy(&self) -> f32 {
 		self.heat_capacity() * self.temperature
@@ -282,42 +658,184 @@ impl Mixture {
 					/ (combined_heat_capacity),
 			);
 		}
-		self.cached_heat_capacity.set(combined_heat_capacity);
+		self.cached_total_moles.invalidate();
+		// a minimum heat capacity isn't additive, so the sum is only our new heat capacity if neither mix has one
+		#[cfg(not(feature = "variable_specific_heat"))]
+		if self.min_heat_capacity <= 0.0 && giver.min_heat_capacity <= 0.0 {
+			self.cached_heat_capacity.set(combined_heat_capacity);
+		} else {
+			self.cached_heat_capacity.invalidate();
+		}
+		// with variable specific heats, the temperature change above changes heat capacity too
+		#[cfg(feature = "variable_specific_heat")]
+		self.cached_heat_capacity.invalidate();
+	}
+	/// Blends this mix towards `other`: each gas's moles and the temperature move linearly from ours to theirs by `t`,
+	/// which is clamped between 0 (unchanged) and 1 (a copy of `other`'s gases and temperature). Gases only one mix has blend from or to zero.
+	/// Unlike `merge`, nothing is added together and energy isn't conserved; this is for smooth transitions, not physics.
+	pub fn interpolate(&mut self, other: &Self, t: f32) {
+		if self.immutable {
+			return;
+		}
+		let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+		self.maybe_expand(other.moles.len());
+		for (idx, amt) in self.moles.iter_mut().enumerate() {
+			let theirs = other.moles.get(idx).copied().unwrap_or(0.0);
+			*amt += (theirs - *amt) * t;
+		}
+		self.set_temperature(self.temperature + (other.temperature - self.temperature) * t);
+		self.invalidate_mole_caches();
+		self.garbage_collect();
+	}
+	/// As `merge`, but the giver's contribution (moles, and thus heat capacity and thermal energy) is scaled by `weight`.
+	/// A weight of 1.0 is identical to `merge`.
+	pub fn merge_scaled(&mut self, giver: &Self, weight: f32) {
+		if self.immutable || !weight.is_normal() || weight < 0.0 {
+			return;
+		}
+		let our_heat_capacity = self.heat_capacity();
+		let other_heat_capacity = giver.heat_capacity() * weight;
+		self.maybe_expand(giver.moles.len());
+		for (a, b) in self.moles.iter_mut().zip(giver.moles.iter()) {
+			*a += b * weight;
+		}
+		let combined_heat_capacity = our_heat_capacity + other_heat_capacity;
+		if combined_heat_capacity > MINIMUM_HEAT_CAPACITY {
+			self.set_temperature(
+				(our_heat_capacity * self.temperature + other_heat_capacity * giver.temperature)
+					/ (combined_heat_capacity),
+			);
+		}
+		self.cached_total_moles.invalidate();
+		// a minimum heat capacity isn't additive, so the sum is only our new heat capacity if neither mix has one
+		#[cfg(not(feature = "variable_specific_heat"))]
+		if self.min_heat_capacity <= 0.0 && giver.min_heat_capacity <= 0.0 {
+			self.cached_heat_capacity.set(combined_heat_capacity);
+		} else {
+			self.cached_heat_capacity.invalidate();
+		}
+		// with variable specific heats, the temperature change above changes heat capacity too
+		#[cfg(feature = "variable_specific_heat")]
+		self.cached_heat_capacity.invalidate();
+	}
+	/// As `merge_scaled`, but takes only as much of the giver as keeps our pressure at or below `max_pressure`.
+	/// Returns the ratio of the giver that was taken, between 0 and 1, so the caller can leave the rest where it was.
+	/// Since the temperature after merging depends on how much is taken, the ratio is found by bisection.
+	pub fn merge_capped(&mut self, giver: &Self, max_pressure: f32) -> f32 {
+		if self.immutable || self.volume <= 0.0 {
+			return 0.0;
+		}
+		let our_heat_capacity = self.heat_capacity();
+		let our_moles = self.total_moles();
+		let their_heat_capacity = giver.heat_capacity();
+		let their_moles = giver.total_moles();
+		let pressure_at = |ratio: f32| {
+			let combined_heat_capacity = our_heat_capacity + their_heat_capacity * ratio;
+			let temperature = if combined_heat_capacity > MINIMUM_HEAT_CAPACITY {
+				(our_heat_capacity * self.temperature
+					+ their_heat_capacity * ratio * giver.temperature)
+					/ combined_heat_capacity
+			} else {
+				self.temperature
+			};
+			(our_moles + their_moles * ratio) * R_IDEAL_GAS_EQUATION * temperature / self.volume
+		};
+		if pressure_at(1.0) <= max_pressure {
+			self.merge(giver);
+			return 1.0;
+		}
+		if pressure_at(0.0) >= max_pressure {
+			return 0.0;
+		}
+		let (mut low, mut high) = (0.0, 1.0);
+		// about as many halvings as an f32 has bits of precision
+		for _ in 0..24 {
+			let mid = (low + high) / 2.0;
+			if pressure_at(mid) <= max_pressure {
+				low = mid;
+			} else {
+				high = mid;
+			}
+		}
+		self.merge_scaled(giver, low);
+		low
 	}
 	/// Transfers only the given gases from us to another mix.
+	/// The moles that leave carry their thermal energy at our temperature, so ours doesn't change and total energy is conserved.
+	/// If we're immutable, the gases are copied rather than moved.
 	pub fn transfer_gases_to(&mut self, r: f32, gases: &[GasIDX], into: &mut Self) {
 		let ratio = r.clamp(0.0, 1.0);
+		self.transfer_gas_ratios(gases.iter().map(|&i| (i, ratio)), into);
+	}
+	/// As `transfer_gases_to`, but each gas has its own ratio, e.g. for a membrane that lets some gases through faster than others.
+	/// Ratios are clamped between 0 and 1; gases not listed stay entirely with us.
+	pub fn remove_ratios_into(&mut self, ratios: &[(GasIDX, f32)], into: &mut Self) {
+		self.transfer_gas_ratios(
+			ratios.iter().map(|&(i, ratio)| (i, ratio.clamp(0.0, 1.0))),
+			into,
+		);
+	}
+	/// As `transfer_gases_to`, but makes the mixture the gases go into on the spot, with our volume, and returns it.
+	#[must_use]
+	pub fn remove_gases_ratio(&mut self, ratio: f32, gases: &[GasIDX]) -> Self {
+		let mut removed = Self::from_vol(self.volume);
+		self.transfer_gases_to(ratio, gases, &mut removed);
+		removed
+	}
+	fn transfer_gas_ratios(
+		&mut self,
+		ratios: impl Iterator<Item = (GasIDX, f32)>,
+		into: &mut Self,
+	) {
 		let initial_energy = into.thermal_energy();
 		let mut heat_transfer = 0.0;
-		with_specific_heats(|heats| {
-			for i in gases.iter().copied() {
-				if let Some(orig) = self.moles.get_mut(i) {
-					let delta = *orig * ratio;
-					heat_transfer += delta * self.temperature * heats[i];
-					*orig -= delta;
-					into.adjust_moles(i, delta);
-				}
+		for (i, ratio) in ratios {
+			let delta = self.get_moles(i) * ratio;
+			if delta <= 0.0 {
+				continue;
 			}
-		});
-		self.cached_heat_capacity.invalidate();
-		into.cached_heat_capacity.invalidate();
+			heat_transfer += delta * self.temperature * self.specific_heat_of(i);
+			if !self.immutable {
+				self.moles[i] -= delta;
+			}
+			into.adjust_moles(i, delta);
+		}
+		if !self.immutable {
+			self.garbage_collect();
+			self.invalidate_mole_caches();
+		}
+		into.invalidate_mole_caches();
 		into.set_temperature((initial_energy + heat_transfer) / into.heat_capacity());
 	}
+	/// Transfers up to `amount` moles in total of the given gases from us to another mix, split proportionally between them.
+	/// Returns how many moles were actually moved, which is less than `amount` if we don't have that many of those gases.
+	pub fn transfer_gases_amount(&mut self, amount: f32, gases: &[GasIDX], into: &mut Self) -> f32 {
+		let available: f32 = gases.iter().map(|&i| self.get_moles(i)).sum();
+		if available <= 0.0 || amount <= 0.0 || !amount.is_finite() {
+			return 0.0;
+		}
+		let ratio = (amount / available).min(1.0);
+		self.transfer_gases_to(ratio, gases, into);
+		available * ratio
+	}
 	/// Takes a percentage of this gas mixture's moles and puts it into another mixture. if this mix is mutable, also removes those moles from the original.
-	pub fn remove_ratio_into(&mut self, mut ratio: f32, into: &mut Self) {
-		if ratio <= 0.0 {
-			return;
+	/// Returns the moles taken, which can be less than asked for if there wasn't that much.
+	pub fn remove_ratio_into(&mut self, mut ratio: f32, into: &mut Self) -> f32 {
+		if ratio <= 0.0 || ratio.is_nan() {
+			return 0.0;
 		}
 		if ratio >= 1.0 {
 			ratio = 1.0;
 		}
+		let removed = self.total_moles() * ratio;
 		into.copy_from_mutable(self);
 		into.multiply(ratio);
 		self.multiply(1.0 - ratio);
+		removed
 	}
 	/// As `remove_ratio_into`, but a raw number of moles instead of a ratio.
-	pub fn remove_into(&mut self, amount: f32, into: &mut Self) {
-		self.remove_ratio_into(amount / self.total_moles(), into);
+	pub fn remove_into(&mut self, amount: f32, into: &mut Self) -> f32 {
+		self.remove_ratio_into(amount / self.total_moles(), into)
 	}
 	/// A convenience function that makes the mixture for `remove_ratio_into` on the spot and returns it.
 	#[must_use]
@@ -326,25 +844,93 @@ impl Mixture {
 		self.remove_ratio_into(ratio, &mut removed);
 		removed
 	}
-	/// Like `remove_ratio`, but with moles.
+	/// Vents some of this mix out of a hole into space, returning what was vented, e.g. to work out the force of the escaping gas.
+	/// `hole_ratio` is the most that can get out at once, between 0 and 1; the higher the pressure, the closer the amount vented gets to that,
+	/// so high-pressure rooms vent faster: half of it at one atmosphere, nine tenths at nine atmospheres.
+	#[must_use]
+	pub fn vent_to_space(&mut self, hole_ratio: f32) -> Self {
+		let pressure = self.return_pressure();
+		if !pressure.is_normal() || pressure <= 0.0 {
+			return Self::from_vol(self.volume);
+		}
+		let ratio = hole_ratio.clamp(0.0, 1.0) * pressure / (pressure + ONE_ATMOSPHERE);
+		self.remove_ratio(ratio)
+	}
+	/// Like `remove_ratio`, but with moles. The moles actually removed are the returned mix's `total_moles`.
 	#[must_use]
 	pub fn remove(&mut self, amount: f32) -> Self {
 		self.remove_ratio(amount / self.total_moles())
 	}
-	/// Copies from a given gas mixture, if we're mutable.
+	/// What this mix would be if it were joined up with all the given mixes, evened out, then split off again at its own volume.
+	/// Moles are weighted by volume, and temperature by heat capacity, as with `equalize_all_gases_in_list`, but none of the mixes are changed.
+	#[must_use]
+	pub fn average_with(&self, others: &[&Self]) -> Self {
+		let mut total = Self::new();
+		total.merge(self);
+		let mut total_volume = self.volume;
+		for other in others {
+			total.merge(other);
+			total_volume += other.volume;
+		}
+		if total_volume > 0.0 {
+			total.multiply(self.volume / total_volume);
+		}
+		total.volume = self.volume;
+		total
+	}
+	/// Splits this mix into `n` equal parts, keeping one and returning the other `n - 1`.
+	/// Each part has `1/n` of every gas and the same temperature and volume as the original.
+	/// If we're immutable, we stay as we are, but the returned parts are still `1/n` of us.
+	pub fn split_evenly(&mut self, n: usize) -> Vec<Self> {
+		if n <= 1 {
+			return Vec::new();
+		}
+		let ratio = 1.0 / n as f32;
+		let mut share = Self::from_vol(self.volume);
+		share.copy_from_mutable(self);
+		share.multiply(ratio);
+		self.multiply(ratio);
+		vec![share; n - 1]
+	}
+	/// Copies from a given gas mixture, if we're mutable. Immutable mixes are never overwritten, whatever the sample, corrupt or not.
 	pub fn copy_from_mutable(&mut self, sample: &Self) {
 		if self.immutable {
 			return;
 		}
-		self.moles = sample.moles.clone();
+		// reuses our allocation, if any, rather than cloning into a new one
+		self.moles.clear();
+		self.moles.extend_from_slice(&sample.moles);
 		self.temperature = sample.temperature;
-		self.cached_heat_capacity = sample.cached_heat_capacity.clone();
+		// we keep our own minimum heat capacity, so the sample's cached heat capacity is only ours if they match
+		if self.min_heat_capacity == sample.min_heat_capacity {
+			self.cached_heat_capacity = sample.cached_heat_capacity.clone();
+		} else {
+			self.cached_heat_capacity.invalidate();
+		}
+		self.cached_total_moles = sample.cached_total_moles.clone();
+	}
+	/// As `copy_from_mutable`, but does nothing if we're already identical to the sample, e.g. when archiving a mix that hasn't changed.
+	/// Compares the moles and temperature directly, which is as cheap as a fingerprint would be and can't be fooled by a collision.
+	/// Returns whether anything was copied.
+	pub fn copy_from_mutable_if_changed(&mut self, sample: &Self) -> bool {
+		if self.immutable
+			|| (self.temperature == sample.temperature && self.moles[..] == sample.moles[..])
+		{
+			return false;
+		}
+		self.copy_from_mutable(sample);
+		true
 	}
 	/// A very simple finite difference solution to the heat transfer equation.
 	/// Works well enough for our purposes, though perhaps called less often
 	/// than it ought to be while we're working in Rust.
 	/// Differs from the original by not using archive, since we don't put the archive into the gas mix itself anymore.
+	/// The conduction coefficient is clamped between 0 and `MAX_CONDUCTION_COEFFICIENT`, since anything above that is unstable.
+	/// With `gas_conductivity`, it's first scaled by `conductivity_factor`.
 	pub fn temperature_share(&mut self, sharer: &mut Self, conduction_coefficient: f32) -> f32 {
+		#[cfg(feature = "gas_conductivity")]
+		let conduction_coefficient = conduction_coefficient * self.conductivity_factor(sharer);
+		let conduction_coefficient = conduction_coefficient.clamp(0.0, MAX_CONDUCTION_COEFFICIENT);
 		let temperature_delta = self.temperature - sharer.temperature;
 		if temperature_delta.abs() > MINIMUM_TEMPERATURE_DELTA_TO_CONSIDER {
 			let self_heat_capacity = self.heat_capacity();
@@ -368,6 +954,72 @@ impl Mixture {
 		}
 		sharer.temperature
 	}
+	/// The average thermal conductivity of the gases in both mixes, weighted by moles, for scaling conduction between them.
+	/// 1 if neither has any gas, same as every gas having the default conductivity.
+	#[cfg(feature = "gas_conductivity")]
+	pub fn conductivity_factor(&self, other: &Self) -> f32 {
+		let total_moles = self.total_moles() + other.total_moles();
+		if total_moles <= 0.0 {
+			return 1.0;
+		}
+		with_gas_info(|gases| {
+			self.moles
+				.iter()
+				.copied()
+				.zip_longest(other.moles.iter().copied())
+				.enumerate()
+				.map(|(idx, amts)| {
+					let (ours, theirs) = amts.or(0.0, 0.0);
+					(ours + theirs) * gases.get(idx).map_or(1.0, |gas| gas.thermal_conductivity)
+				})
+				.sum::<f32>()
+				/ total_moles
+		})
+	}
+	/// The mole equivalent of `temperature_share`: moves each gas a `coefficient` fraction of the way
+	/// towards both mixes having the same concentration of it, with the moved moles carrying their thermal energy.
+	/// A coefficient of 1 equalizes concentrations fully, like `equalize_with`; it's clamped between 0 and 1.
+	/// Moles and energy are conserved, unless one of the mixes is immutable, in which case it acts as an infinite reservoir.
+	pub fn gas_share(&mut self, other: &mut Self, coefficient: f32) {
+		let coefficient = coefficient.clamp(0.0, 1.0);
+		let total_volume = self.volume + other.volume;
+		if total_volume <= 0.0 || !total_volume.is_normal() || coefficient <= 0.0 {
+			return;
+		}
+		let our_share = self.volume / total_volume;
+		let mut our_energy = self.thermal_energy();
+		let mut their_energy = other.thermal_energy();
+		for i in 0..self.moles.len().max(other.moles.len()) {
+			let ours = self.get_moles(i);
+			let theirs = other.get_moles(i);
+			// positive if we're giving, negative if we're taking
+			let delta = coefficient * (ours - (ours + theirs) * our_share);
+			if !delta.is_normal() {
+				continue;
+			}
+			let energy = if delta > 0.0 {
+				delta * self.specific_heat_of(i) * self.temperature
+			} else {
+				delta * other.specific_heat_of(i) * other.temperature
+			};
+			if !self.immutable {
+				self.adjust_moles(i, -delta);
+				our_energy -= energy;
+			}
+			if !other.immutable {
+				other.adjust_moles(i, delta);
+				their_energy += energy;
+			}
+		}
+		let our_heat_capacity = self.heat_capacity();
+		if our_heat_capacity > MINIMUM_HEAT_CAPACITY {
+			self.set_temperature(our_energy / our_heat_capacity);
+		}
+		let their_heat_capacity = other.heat_capacity();
+		if their_heat_capacity > MINIMUM_HEAT_CAPACITY {
+			other.set_temperature(their_energy / their_heat_capacity);
+		}
+	}
 	/// As above, but you may put in any arbitrary coefficient, temp, heat capacity.
 	/// Only used for superconductivity as of right now.
 	pub fn temperature_share_non_gas(
@@ -376,6 +1028,7 @@ impl Mixture {
 		sharer_temperature: f32,
 		sharer_heat_capacity: f32,
 	) -> f32 {
+		let conduction_coefficient = conduction_coefficient.clamp(0.0, MAX_CONDUCTION_COEFFICIENT);
 		let temperature_delta = self.temperature - sharer_temperature;
 		if temperature_delta.abs() > MINIMUM_TEMPERATURE_DELTA_TO_CONSIDER {
 			let self_heat_capacity = self.heat_capacity();
@@ -394,12 +1047,138 @@ impl Mixture {
 		}
 		sharer_temperature
 	}
+	/// Moves this mix's temperature `coefficient` of the way towards that of a heat sink with infinite thermal mass, such as a wall or space.
+	/// The coefficient is clamped between 0 and `MAX_CONDUCTION_COEFFICIENT`.
+	/// Returns the heat that went into the mix, in joules; negative if it lost heat to the sink.
+	pub fn conduct_to_sink(&mut self, sink_temperature: f32, coefficient: f32) -> f32 {
+		let coefficient = coefficient.clamp(0.0, MAX_CONDUCTION_COEFFICIENT);
+		let temperature_delta = sink_temperature - self.temperature;
+		if self.immutable || temperature_delta.abs() <= MINIMUM_TEMPERATURE_DELTA_TO_CONSIDER {
+			return 0.0;
+		}
+		let new_temperature = (self.temperature + coefficient * temperature_delta).max(TCMB);
+		let heat = (new_temperature - self.temperature) * self.heat_capacity();
+		self.set_temperature(new_temperature);
+		heat
+	}
+	/// Conducts heat between this mix, an external thermal mass such as a turf, and any number of neighboring mixes,
+	/// each with its own conduction coefficient. Heat is shared with the thermal mass first, then with each neighbor in turn.
+	/// Every exchange is pairwise, so the group's total energy is conserved, barring the TCMB floor.
+	/// Returns: the thermal mass's new temperature.
+	pub fn superconduct<'a>(
+		&mut self,
+		neighbors: impl IntoIterator<Item = (&'a mut Self, f32)>,
+		turf_coefficient: f32,
+		turf_heat_capacity: f32,
+		turf_temperature: f32,
+	) -> f32 {
+		let turf_temperature =
+			self.temperature_share_non_gas(turf_coefficient, turf_temperature, turf_heat_capacity);
+		for (neighbor, coefficient) in neighbors {
+			self.temperature_share(neighbor, coefficient);
+		}
+		turf_temperature
+	}
+	/// Conducts heat between this mix and several neighbors at once, each with its own conduction coefficient.
+	/// Unlike `superconduct`, every exchange is worked out from the temperatures everything started at, so the order of the neighbors doesn't matter.
+	/// Coefficients are clamped as in `temperature_share`; if they add up to more than `MAX_CONDUCTION_COEFFICIENT`, they're all scaled down to fit, so we can't overshoot.
+	/// Energy is conserved across the group, barring immutable mixes and the TCMB floor.
+	/// Returns: the heat that went into each neighbor, in joules, in the same order; negative if it went the other way.
+	pub fn conduct_with_many(&mut self, neighbors: &mut [(&mut Self, f32)]) -> Vec<f32> {
+		let self_heat_capacity = self.heat_capacity();
+		let coefficients: Vec<f32> = neighbors
+			.iter()
+			.map(|(_neighbor, coefficient)| {
+				let coefficient = *coefficient;
+				#[cfg(feature = "gas_conductivity")]
+				let coefficient = coefficient * self.conductivity_factor(_neighbor);
+				coefficient.clamp(0.0, MAX_CONDUCTION_COEFFICIENT)
+			})
+			.collect();
+		let total_coefficient: f32 = coefficients.iter().sum();
+		let scale = if total_coefficient > MAX_CONDUCTION_COEFFICIENT {
+			MAX_CONDUCTION_COEFFICIENT / total_coefficient
+		} else {
+			1.0
+		};
+		let fluxes: Vec<f32> = neighbors
+			.iter()
+			.zip(coefficients.iter())
+			.map(|((neighbor, _), &coefficient)| {
+				let temperature_delta = self.temperature - neighbor.temperature;
+				let neighbor_heat_capacity = neighbor.heat_capacity();
+				if temperature_delta.abs() > MINIMUM_TEMPERATURE_DELTA_TO_CONSIDER
+					&& self_heat_capacity > MINIMUM_HEAT_CAPACITY
+					&& neighbor_heat_capacity > MINIMUM_HEAT_CAPACITY
+				{
+					let conductance = self_heat_capacity * neighbor_heat_capacity
+						/ (self_heat_capacity + neighbor_heat_capacity);
+					coefficient * scale * temperature_delta * conductance
+				} else {
+					0.0
+				}
+			})
+			.collect();
+		for ((neighbor, _), &flux) in neighbors.iter_mut().zip(fluxes.iter()) {
+			if flux != 0.0 && !neighbor.immutable {
+				let new_temperature = neighbor.temperature + flux / neighbor.heat_capacity();
+				neighbor.set_temperature(new_temperature.max(TCMB));
+			}
+		}
+		let total_flux: f32 = fluxes.iter().sum();
+		if total_flux != 0.0 && !self.immutable {
+			self.set_temperature((self.temperature - total_flux / self_heat_capacity).max(TCMB));
+		}
+		fluxes
+	}
 	/// The second part of old compare(). Compares temperature, but only if this gas has sufficiently high moles.
 	pub fn temperature_compare(&self, sample: &Self) -> bool {
 		(self.get_temperature() - sample.get_temperature()).abs()
 			> MINIMUM_TEMPERATURE_DELTA_TO_SUSPEND
 			&& (self.total_moles() > MINIMUM_MOLES_DELTA_TO_MOVE)
 	}
+	/// Removes every gas the mix is too cold to hold, as if it had liquefied out.
+	/// `thresholds` has the condensation temperature of each gas that can condense; gases not in it never do.
+	/// The removed gases take their share of the thermal energy with them, so the rest of the mix keeps its temperature.
+	/// Returns: each gas removed, along with how many moles of it were removed.
+	pub fn condense_below(&mut self, thresholds: &[(GasIDX, f32)]) -> Vec<(GasIDX, f32)> {
+		let mut removed = Vec::new();
+		if self.immutable {
+			return removed;
+		}
+		for &(idx, threshold) in thresholds {
+			let amt = self.get_moles(idx);
+			if self.temperature < threshold && amt > GAS_MIN_MOLES {
+				self.set_moles(idx, 0.0);
+				removed.push((idx, amt));
+			}
+		}
+		self.garbage_collect();
+		removed
+	}
+	/// Whether the two mixes are effectively identical: every gas within `moles_tolerance` moles, and temperatures within `temperature_tolerance` kelvins.
+	/// Unlike `==`, a gas missing from one mix counts as zero moles of it, rather than making them unequal.
+	pub fn approx_eq(
+		&self,
+		other: &Self,
+		moles_tolerance: f32,
+		temperature_tolerance: f32,
+	) -> bool {
+		(self.temperature - other.temperature).abs() <= temperature_tolerance
+			&& self
+				.moles
+				.iter()
+				.copied()
+				.zip_longest(other.moles.iter().copied())
+				.all(|pair| pair.reduce(|a, b| (b - a).abs()) <= moles_tolerance)
+	}
+	/// The first gas whose moles differ between the two mixes by more than `tolerance`, with our moles of it and then theirs.
+	/// For saying which gas made two mixes unequal, e.g. in test failures.
+	pub fn first_difference(&self, other: &Self, tolerance: f32) -> Option<(GasIDX, f32, f32)> {
+		(0..self.moles.len().max(other.moles.len()))
+			.map(|i| (i, self.get_moles(i), other.get_moles(i)))
+			.find(|&(_, ours, theirs)| (ours - theirs).abs() > tolerance)
+	}
 	/// Returns the maximum mole delta for an individual gas.
 	pub fn compare(&self, sample: &Self) -> f32 {
 		self.moles
@@ -408,6 +1187,30 @@ impl Mixture {
 			.zip_longest(sample.moles.iter().copied())
 			.fold(0.0, |acc, pair| acc.max(pair.reduce(|a, b| (b - a).abs())))
 	}
+	/// As `compare`, but also says which gas has that delta: the first, if several do. `(0, 0.0)` if no gas differs at all.
+	pub fn compare_detailed(&self, sample: &Self) -> (GasIDX, f32) {
+		self.moles
+			.iter()
+			.copied()
+			.zip_longest(sample.moles.iter().copied())
+			.enumerate()
+			.map(|(i, pair)| (i, pair.reduce(|a, b| (b - a).abs())))
+			// max_by picks the last of equal deltas, so go backwards to get the first
+			.rev()
+			.max_by(|(_, a), (_, b)| a.total_cmp(b))
+			.unwrap_or((0, 0.0))
+	}
+	/// As `compare`, but returns every gas whose absolute mole delta is more than `threshold`, along with that delta.
+	pub fn gas_differences(&self, sample: &Self, threshold: f32) -> Vec<(GasIDX, f32)> {
+		self.moles
+			.iter()
+			.copied()
+			.zip_longest(sample.moles.iter().copied())
+			.enumerate()
+			.map(|(i, pair)| (i, pair.reduce(|a, b| (b - a).abs())))
+			.filter(|&(_, delta)| delta > threshold)
+			.collect()
+	}
 	pub fn compare_with(&self, sample: &Self, amt: f32) -> bool {
 		self.moles
 			.as_slice()
@@ -424,13 +1227,20 @@ impl Mixture {
 	pub fn clear(&mut self) {
 		if !self.immutable {
 			self.moles.clear();
-			self.cached_heat_capacity.invalidate();
+			self.invalidate_mole_caches();
 		}
 	}
+	/// Clears the moles from the gas, explicitly keeping its temperature and volume, unlike `clear_with_vol`.
+	/// Currently the same as `clear`, but callers relying on the temperature staying put should use this.
+	pub fn clear_keep_temperature(&mut self) {
+		self.clear();
+	}
 	/// Resets the gas mixture to an initialized-with-volume state.
 	pub fn clear_with_vol(&mut self, vol: f32) {
 		self.temperature = 2.7;
 		self.volume = vol;
+		#[cfg(feature = "variable_specific_heat")]
+		self.cached_heat_capacity.invalidate();
 		self.min_heat_capacity = 0.0;
 		self.immutable = false;
 		self.clear();
@@ -441,16 +1251,50 @@ impl Mixture {
 			for amt in self.moles.iter_mut() {
 				*amt *= multiplier;
 			}
-			self.cached_heat_capacity.invalidate();
+			self.invalidate_mole_caches();
 			self.garbage_collect();
 		}
 	}
+	/// Sets the volume, scaling every gas by the same factor so the pressure stays the same. Doesn't change the temperature.
+	/// If the old volume wasn't positive, there's nothing sensible to scale by, so only the volume changes.
+	/// # Errors
+	/// If the new volume isn't a positive number.
+	pub fn scale_volume(&mut self, new_volume: f32) -> Result<(), auxtools::Runtime> {
+		if !new_volume.is_finite() || new_volume <= 0.0 {
+			return Err(auxtools::runtime!(
+				"Attempted to scale volume to {}, which isn't a positive number.",
+				new_volume
+			));
+		}
+		if self.immutable {
+			return Ok(());
+		}
+		if self.volume > 0.0 {
+			self.multiply(new_volume / self.volume);
+		}
+		self.volume = new_volume;
+		Ok(())
+	}
+	/// Takes the amount of each gas in `other` away from this mix, stopping at zero, without changing the temperature,
+	/// e.g. to take out reactants worked out elsewhere. Unlike `remove_ratio`, which takes a fraction of everything, this takes exact amounts.
+	pub fn subtract(&mut self, other: &Self) {
+		if self.immutable {
+			return;
+		}
+		for (amt, &taken) in self.moles.iter_mut().zip(other.moles.iter()) {
+			if taken > 0.0 {
+				*amt = (*amt - taken).max(0.0);
+			}
+		}
+		self.invalidate_mole_caches();
+		self.garbage_collect();
+	}
 	pub fn add(&mut self, num: f32) {
 		if !self.immutable {
 			for amt in self.moles.iter_mut() {
 				*amt += num;
 			}
-			self.cached_heat_capacity.invalidate();
+			self.invalidate_mole_caches();
 			self.garbage_collect();
 		}
 	}
@@ -458,38 +1302,221 @@ impl Mixture {
 		&self,
 		reactions: &BTreeMap<ReactionPriority, Reaction>,
 	) -> bool {
+		let gases = self.gases_bitfield();
 		//priorities are inversed because fuck you
 		reactions
 			.values()
 			.rev()
-			.any(|reaction| reaction.check_conditions(self))
+			.any(|reaction| reaction.check_conditions_with_bitfield(self, gases))
 	}
-	/// Checks if the proc can react with any reactions.
+	/// Checks if the proc can react with any reactions. False if reactions aren't loaded yet.
 	pub fn can_react(&self) -> bool {
-		with_reactions(|reactions| self.can_react_with_reactions(reactions))
+		with_reactions(|reactions| self.can_react_with_reactions(reactions)).unwrap_or(false)
+	}
+	/// How vigorously this mix is reacting, from 0 to 1, e.g. for scaling fire brightness.
+	/// The average of `Reaction::margin_past_requirements` over every reaction it can do; 0 if it can't react, or reactions aren't loaded yet.
+	pub fn reaction_intensity(&self) -> f32 {
+		let gases = self.gases_bitfield();
+		with_reactions(|reactions| {
+			let (total, count) = reactions
+				.values()
+				.filter(|reaction| reaction.check_conditions_with_bitfield(self, gases))
+				.fold((0.0, 0), |(total, count), reaction| {
+					(total + reaction.margin_past_requirements(self), count + 1)
+				});
+			if count == 0 {
+				0.0
+			} else {
+				total / count as f32
+			}
+		})
+		.unwrap_or(0.0)
 	}
 	pub fn all_reactable_with_slice(
 		&self,
 		reactions: &BTreeMap<ReactionPriority, Reaction>,
 	) -> TinyVec<[u64; MAX_REACTION_TINYVEC_SIZE]> {
+		let gases = self.gases_bitfield();
 		//priorities are inversed because fuck you
 		reactions
 			.values()
 			.rev()
-			.filter_map(|thin| thin.check_conditions(self).then(|| thin.get_id()))
+			.filter(|thin| thin.check_conditions_with_bitfield(self, gases))
+			.map(Reaction::get_id)
 			.collect()
 	}
 	/// Gets all of the reactions this mix should do.
-	pub fn all_reactable(&self) -> TinyVec<[u64; MAX_REACTION_TINYVEC_SIZE]> {
-		with_reactions(|reactions| self.all_reactable_with_slice(reactions))
+	/// If the mix is in the same state as the last time it had nothing that could react, skips checking the reactions.
+	/// # Errors
+	/// If reactions aren't loaded yet.
+	pub fn all_reactable(
+		&self,
+	) -> Result<TinyVec<[u64; MAX_REACTION_TINYVEC_SIZE]>, auxtools::Runtime> {
+		let state_hash = self.reaction_state_hash();
+		if self.no_reaction_cache.0.load(Relaxed) == state_hash {
+			return Ok(TinyVec::new());
+		}
+		let reactable = with_reactions(|reactions| self.all_reactable_with_slice(reactions))?;
+		if reactable.is_empty() {
+			self.no_reaction_cache.0.store(state_hash, Relaxed);
+		}
+		Ok(reactable)
 	}
-	/// Returns a tuple with oxidation power and fuel amount of this gas mixture.
-	pub fn get_burnability(&self) -> (f32, f32) {
-		use crate::types::FireInfo;
-		super::with_gas_info(|gas_info| {
-			self.moles
-				.iter()
-				.zip(gas_info)
+	/// A hash of everything reaction conditions can depend on: the mix's temperature, volume and gases, and the loaded reactions.
+	/// The minimum heat capacity counts too, since it can raise the thermal energy that energy requirements check.
+	fn reaction_state_hash(&self) -> u64 {
+		use std::hash::Hasher;
+		let mut hasher = fxhash::FxHasher64::default();
+		hasher.write_u64(reaction_generation());
+		hasher.write_u32(self.temperature.to_bits());
+		hasher.write_u32(self.volume.to_bits());
+		hasher.write_u32(self.min_heat_capacity.to_bits());
+		for (i, amt) in self.enumerate() {
+			hasher.write_usize(i);
+			hasher.write_u32(amt.to_bits());
+		}
+		// 0 means nothing's cached
+		hasher.finish().max(1)
+	}
+	/// Each gas's mole fraction, rounded to the nearest `SIGNATURE_BINS`th; gases that round to nothing are left out.
+	fn signature_bins(&self) -> Vec<(GasIDX, u32)> {
+		let total_moles = self.total_moles();
+		if total_moles <= 0.0 {
+			return Vec::new();
+		}
+		let bins = SIGNATURE_BINS as f32;
+		self.enumerate()
+			.map(|(i, amt)| (i, (amt / total_moles * bins).round() as u32))
+			.filter(|&(_, bin)| bin > 0)
+			.collect()
+	}
+	/// A hash of the mix's composition, i.e. its mole fractions, binned by `signature_bins`.
+	/// Mixes of the same composition get the same signature however many moles they have, and whatever their volume or temperature.
+	pub fn spectral_signature(&self) -> u64 {
+		use std::hash::Hasher;
+		let mut hasher = fxhash::FxHasher64::default();
+		for (i, bin) in self.signature_bins() {
+			hasher.write_usize(i);
+			hasher.write_u32(bin);
+		}
+		hasher.finish()
+	}
+	/// How similar the compositions of the two mixes are, from 0 (nothing in common) to 1 (the same signature),
+	/// as the overlap of their binned mole fractions. Two empty mixes are identical.
+	pub fn signature_similarity(&self, other: &Self) -> f32 {
+		let ours = self.signature_bins();
+		let theirs = other.signature_bins();
+		let total = ours
+			.iter()
+			.map(|&(_, bin)| bin)
+			.sum::<u32>()
+			.max(theirs.iter().map(|&(_, bin)| bin).sum());
+		if total == 0 {
+			return 1.0;
+		}
+		let overlap: u32 = ours
+			.iter()
+			.filter_map(|&(i, bin)| {
+				theirs
+					.iter()
+					.find(|&&(j, _)| i == j)
+					.map(|&(_, other_bin)| bin.min(other_bin))
+			})
+			.sum();
+		overlap as f32 / total as f32
+	}
+	/// Packs the mix into bytes, e.g. to save it across rounds; `deserialize` unpacks them. Everything is little-endian:
+	/// - `u8` `SERIALIZATION_VERSION`
+	/// - `f32` volume, then `f32` temperature, then `f32` minimum heat capacity
+	/// - `u32` how many gases follow, then that many pairs of `u32` gas index and `f32` moles, for every gas with any moles
+	///
+	/// Whether the mix is immutable isn't saved.
+	pub fn serialize(&self) -> Vec<u8> {
+		let gases = self
+			.enumerate()
+			.filter(|&(_, amt)| amt > 0.0)
+			.collect::<Vec<_>>();
+		let mut bytes = Vec::with_capacity(17 + 8 * gases.len());
+		bytes.push(SERIALIZATION_VERSION);
+		bytes.extend_from_slice(&self.volume.to_le_bytes());
+		bytes.extend_from_slice(&self.temperature.to_le_bytes());
+		bytes.extend_from_slice(&self.min_heat_capacity.to_le_bytes());
+		bytes.extend_from_slice(&(gases.len() as u32).to_le_bytes());
+		for (idx, amt) in gases {
+			bytes.extend_from_slice(&(idx as u32).to_le_bytes());
+			bytes.extend_from_slice(&amt.to_le_bytes());
+		}
+		bytes
+	}
+	/// Unpacks a mix packed by `serialize`, including by older versions of it.
+	/// # Errors
+	/// If the bytes are cut short or run on, come from an unknown version, or have an invalid volume, temperature or minimum heat capacity,
+	/// a gas index that isn't a valid gas, or an amount that isn't a finite, non-negative number.
+	pub fn deserialize(bytes: &[u8]) -> Result<Self, auxtools::Runtime> {
+		let (&version, mut rest) = bytes
+			.split_first()
+			.ok_or_else(|| auxtools::runtime!("Serialized gas mixture is empty"))?;
+		// each version has its own reader; when the format changes, older versions' readers
+		// bring what they read up to date, e.g. by filling in anything they didn't have
+		let mix = match version {
+			1 => Self::deserialize_v1(&mut rest)?,
+			_ => {
+				return Err(auxtools::runtime!(
+					"Serialized gas mixture has unknown version {}; the newest known is {}",
+					version,
+					SERIALIZATION_VERSION
+				))
+			}
+		};
+		if !rest.is_empty() {
+			return Err(auxtools::runtime!(
+				"Serialized gas mixture has {} bytes too many",
+				rest.len()
+			));
+		}
+		Ok(mix)
+	}
+	fn deserialize_v1(bytes: &mut &[u8]) -> Result<Self, auxtools::Runtime> {
+		let volume = f32::from_le_bytes(take_bytes(bytes)?);
+		let temperature = f32::from_le_bytes(take_bytes(bytes)?);
+		let min_heat_capacity = f32::from_le_bytes(take_bytes(bytes)?);
+		let count = u32::from_le_bytes(take_bytes(bytes)?) as usize;
+		if !volume.is_finite() || volume < 0.0 || !temperature.is_normal() || temperature < 0.0 {
+			return Err(auxtools::runtime!(
+				"Serialized gas mixture has an invalid volume ({}) or temperature ({})",
+				volume,
+				temperature
+			));
+		}
+		if !min_heat_capacity.is_finite() || min_heat_capacity < 0.0 {
+			return Err(auxtools::runtime!(
+				"Serialized gas mixture has an invalid minimum heat capacity ({})",
+				min_heat_capacity
+			));
+		}
+		// checked up front so a bad count can't make us allocate a huge vector, or overflow on 32-bit targets
+		if count.checked_mul(8).map_or(true, |len| bytes.len() < len) {
+			return Err(auxtools::runtime!("Serialized gas mixture is cut short"));
+		}
+		let gases = (0..count)
+			.map(|_| {
+				let idx = u32::from_le_bytes(take_bytes(bytes)?) as GasIDX;
+				Ok((idx, f32::from_le_bytes(take_bytes(bytes)?)))
+			})
+			.collect::<Result<Vec<_>, auxtools::Runtime>>()?;
+		let mut mix = Self::from_vol(volume);
+		mix.try_set_multi(&gases)?;
+		mix.set_temperature(temperature);
+		mix.set_min_heat_capacity(min_heat_capacity);
+		Ok(mix)
+	}
+	/// Returns a tuple with oxidation power and fuel amount of this gas mixture.
+	pub fn get_burnability(&self) -> (f32, f32) {
+		use crate::types::FireInfo;
+		super::with_gas_info(|gas_info| {
+			self.moles
+				.iter()
+				.zip(gas_info)
 				.fold((0.0, 0.0), |mut acc, (&amt, this_gas_info)| {
 					if amt > GAS_MIN_MOLES {
 						match this_gas_info.fire_info {
@@ -558,31 +1585,86 @@ impl Mixture {
 			.partition_map(|r| r)
 	}
 	/// Returns two vectors:
-	/// The first contains all oxidizers in this list, as well as their actual mole amounts and how much fuel they can oxidize.
-	/// The second contains all fuel sources in this list, as well as their actual mole amounts and how much oxidizer they can react with.
+	/// The first contains all fuel sources in this list, as well as their actual mole amounts and how much oxidizer they can react with.
+	/// The second contains all oxidizers in this list, as well as their actual mole amounts and how much fuel they can oxidize.
 	pub fn get_fire_info(&self) -> (Vec<SpecificFireInfo>, Vec<SpecificFireInfo>) {
 		super::with_gas_info(|gas_info| self.get_fire_info_with_lock(gas_info))
 	}
 	/// Adds heat directly to the gas mixture, in joules (probably).
+	/// Does nothing if the heat capacity is at or below `MINIMUM_HEAT_CAPACITY`, e.g. an empty mix without a minimum heat capacity,
+	/// since there's nothing to hold the heat; the temperature is left as it was rather than becoming NaN or infinite.
+	/// Taking away more heat than the mix has won't cool it below TCMB.
 	pub fn adjust_heat(&mut self, heat: f32) {
 		let cap = self.heat_capacity();
-		self.set_temperature(((cap * self.temperature) + heat) / cap);
+		if cap <= MINIMUM_HEAT_CAPACITY {
+			return;
+		}
+		self.set_temperature((((cap * self.temperature) + heat) / cap).max(TCMB));
+	}
+	/// Applies a reaction's whole effect at once: adjusts each gas by its delta, as in `adjust_multi`,
+	/// then adds `heat` joules using the heat capacity after those changes, so the heat goes into the gases actually there.
+	pub fn apply_reaction_effect(&mut self, deltas: &[(GasIDX, f32)], heat: f32) {
+		if self.immutable {
+			return;
+		}
+		self.adjust_multi(deltas);
+		self.adjust_heat(heat);
+	}
+	/// As `adjust_heat`, but won't heat the mix past `max_temperature`, like a heater with a thermostat.
+	/// Removing heat isn't capped, except by `adjust_heat` itself not cooling past TCMB.
+	/// Returns the heat actually added, in joules, going by how much the temperature changed,
+	/// so it's 0 if `adjust_heat` had nowhere to put the heat.
+	pub fn adjust_heat_capped(&mut self, heat: f32, max_temperature: f32) -> f32 {
+		let cap = self.heat_capacity();
+		if self.immutable || !cap.is_normal() {
+			return 0.0;
+		}
+		let old_temperature = self.temperature;
+		self.adjust_heat(heat.min(((max_temperature - old_temperature) * cap).max(0.0)));
+		(self.temperature - old_temperature) * cap
+	}
+	/// How much of the given amount of gas in this mix counts towards its visibility, in the threshold's units:
+	/// the moles as they are, or the partial pressure they make here for gases visible by pressure.
+	pub fn visibility_amount(&self, moles: f32, visibility: GasVisibility) -> f32 {
+		match visibility {
+			GasVisibility::Moles(_) => moles,
+			GasVisibility::PartialPressure(_) if self.volume > 0.0 => {
+				moles * R_IDEAL_GAS_EQUATION * self.temperature / self.volume
+			}
+			GasVisibility::PartialPressure(_) => 0.0,
+		}
 	}
 	/// Returns true if there's a visible gas in this mix.
 	pub fn is_visible(&self) -> bool {
-		self.enumerate()
-			.any(|(i, gas)| gas_visibility(i as usize).map_or(false, |amt| gas >= amt))
+		self.enumerate().any(|(i, gas)| {
+			gas_visibility(i as usize)
+				.ok()
+				.flatten()
+				.map_or(false, |vis| {
+					self.visibility_amount(gas, vis) >= vis.threshold()
+				})
+		})
+	}
+	/// Returns true if this mix is worth processing: it can react, has a visible gas,
+	/// or is different enough from any of the given neighbors to share with them, as in the `compare` hook.
+	pub fn needs_processing(&self, neighbors: &[&Self]) -> bool {
+		let min_moles_delta = minimum_moles_delta();
+		self.can_react()
+			|| self.is_visible()
+			|| neighbors.iter().any(|neighbor| {
+				self.temperature_compare(neighbor) || self.compare_with(neighbor, min_moles_delta)
+			})
 	}
-	pub fn vis_hash(&self, gas_visibility: &[Option<f32>]) -> u64 {
+	pub fn vis_hash(&self, gas_visibility: &[Option<GasVisibility>]) -> u64 {
 		use std::hash::Hasher;
 		let mut hasher: ahash::AHasher = ahash::AHasher::default();
 		for (i, gas_amt) in self.enumerate() {
-			if unsafe { gas_visibility.get_unchecked(i) }
-				.filter(|&amt| gas_amt > amt)
-				.is_some()
-			{
-				hasher.write_usize(i);
-				hasher.write_usize(visibility_step(gas_amt) as usize)
+			if let Some(vis) = unsafe { gas_visibility.get_unchecked(i) } {
+				let amount = self.visibility_amount(gas_amt, *vis);
+				if amount > vis.threshold() {
+					hasher.write_usize(i);
+					hasher.write_usize(vis.step(amount) as usize)
+				}
 			}
 		}
 		hasher.finish()
@@ -590,7 +1672,7 @@ impl Mixture {
 	/// Compares the current vis hash to the provided one; returns true if they are
 	pub fn vis_hash_changed(
 		&self,
-		gas_visibility: &[Option<f32>],
+		gas_visibility: &[Option<GasVisibility>],
 		hash_holder: &AtomicU64,
 	) -> bool {
 		let cur_hash = self.vis_hash(gas_visibility);
@@ -603,18 +1685,38 @@ impl Mixture {
 	// Removes all redundant zeroes from the gas mixture.
 	pub fn garbage_collect(&mut self) {
 		let mut last_valid_found = 0;
+		let mut zeroed = false;
 		for (i, amt) in self.moles.iter_mut().enumerate() {
 			if *amt > GAS_MIN_MOLES {
 				last_valid_found = i;
-			} else {
+			} else if *amt != 0.0 {
 				*amt = 0.0;
+				zeroed = true;
 			}
 		}
 		self.moles.truncate(last_valid_found + 1);
+		if zeroed {
+			self.invalidate_mole_caches();
+		}
+	}
+	/// Invalidates everything cached from the moles. Must be called whenever they change.
+	fn invalidate_mole_caches(&self) {
+		self.cached_heat_capacity.invalidate();
+		self.cached_total_moles.invalidate();
+	}
+	/// Puts the mix back the way `new` makes it, but keeps the allocation for the moles.
+	fn reset(&mut self) {
+		self.moles.clear();
+		self.temperature = 2.7;
+		self.volume = 2500.0;
+		self.min_heat_capacity = 0.0;
+		self.immutable = false;
+		self.invalidate_mole_caches();
+		self.no_reaction_cache = NoReactionCache::default();
 	}
 }
 
-use std::ops::{Add, Mul};
+use std::ops::{Add, Deref, DerefMut, Mul};
 
 /// Takes a copy of the mix, merges the right hand side, then returns the copy.
 impl Add<&Mixture> for Mixture {
@@ -660,6 +1762,16 @@ impl<'a> Mul<f32> for &'a Mixture {
 	}
 }
 
+/// Takes the next `N` bytes off the front of `bytes`, for `Mixture::deserialize`.
+fn take_bytes<const N: usize>(bytes: &mut &[u8]) -> Result<[u8; N], auxtools::Runtime> {
+	if bytes.len() < N {
+		return Err(auxtools::runtime!("Serialized gas mixture is cut short"));
+	}
+	let (taken, rest) = bytes.split_at(N);
+	*bytes = rest;
+	Ok(taken.try_into().unwrap())
+}
+
 impl PartialEq for Mixture {
 	fn eq(&self, other: &Self) -> bool {
 		self.moles.len() == other.moles.len()
@@ -674,6 +1786,65 @@ impl PartialEq for Mixture {
 
 impl Eq for Mixture {}
 
+/// How many scratch mixtures each thread keeps around to reuse.
+const SCRATCH_POOL_SIZE: usize = 8;
+
+thread_local! {
+	static SCRATCH_MIXES: RefCell<Vec<Mixture>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A temporary gas mixture for work DM never sees, e.g. the buffers when sharing or equalizing.
+/// Comes from a per-thread pool instead of the gas arena, so it doesn't lock anything and, once warmed up,
+/// doesn't allocate either; if the pool's empty, e.g. with nested scratch mixtures, it's just a new mixture.
+/// Goes back to the pool, reset to empty, when dropped.
+pub struct ScratchMix(Mixture);
+
+impl ScratchMix {
+	/// Takes an empty mixture from this thread's pool, or makes one if there's none left.
+	#[must_use]
+	pub fn new() -> Self {
+		Self(
+			SCRATCH_MIXES
+				.with(|pool| pool.borrow_mut().pop())
+				.unwrap_or_default(),
+		)
+	}
+}
+
+impl Default for ScratchMix {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Deref for ScratchMix {
+	type Target = Mixture;
+
+	fn deref(&self) -> &Mixture {
+		&self.0
+	}
+}
+
+impl DerefMut for ScratchMix {
+	fn deref_mut(&mut self) -> &mut Mixture {
+		&mut self.0
+	}
+}
+
+impl Drop for ScratchMix {
+	fn drop(&mut self) {
+		let mut mix = std::mem::take(&mut self.0);
+		mix.reset();
+		// try_with, since this can run while the thread's shutting down and the pool's already gone
+		let _ = SCRATCH_MIXES.try_with(|pool| {
+			let mut pool = pool.borrow_mut();
+			if pool.len() < SCRATCH_POOL_SIZE {
+				pool.push(mix);
+			}
+		});
+	}
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -741,4 +1912,1002 @@ mod tests {
 		assert_eq!(new_two.get_moles(0), 5.5);
 		destroy_gas_statics();
 	}
+	#[test]
+	fn test_temperature_share_overshoot() {
+		initialize_gases();
+		let mut hot = Mixture::new();
+		hot.set_moles(0, 100.0);
+		hot.set_temperature(1000.0);
+		let mut cold = Mixture::new();
+		cold.set_moles(1, 100.0);
+		cold.set_temperature(100.0);
+		hot.temperature_share(&mut cold, 2.0);
+		assert!(
+			hot.get_temperature() >= cold.get_temperature(),
+			"{} should not be colder than {}",
+			hot.get_temperature(),
+			cold.get_temperature()
+		);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_transfer_gases_energy_conservation() {
+		set_gas_statics_manually();
+		register_gas_manually("o2", 20.0);
+		register_gas_manually("n2", 20.0);
+		register_gas_manually("plasma", 200.0);
+		let mut source = Mixture::new();
+		source.set_moles(0, 50.0);
+		source.set_moles(1, 80.0);
+		source.set_moles(2, 30.0);
+		source.set_temperature(500.0);
+		let mut into = Mixture::new();
+		into.set_moles(0, 40.0);
+		into.set_temperature(300.0);
+		let initial_energy = source.thermal_energy() + into.thermal_energy();
+		source.transfer_gases_to(0.4, &[0, 2], &mut into);
+		assert_eq!(source.get_moles(1), 80.0);
+		assert!((source.get_moles(2) - 18.0).abs() < 0.001);
+		assert!((into.get_moles(2) - 12.0).abs() < 0.001);
+		assert_eq!(source.get_temperature(), 500.0);
+		let final_energy = source.thermal_energy() + into.thermal_energy();
+		assert!(
+			((final_energy - initial_energy) / initial_energy).abs() < 0.0001,
+			"energy went from {} to {}",
+			initial_energy,
+			final_energy
+		);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_validate_indices_against_gas_table() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(2, 10.0);
+		mix.set_temperature(300.0);
+		assert!(mix.validate_indices_against_gas_table());
+		mix.moles.extend_from_slice(&[0.0, 0.0]);
+		assert!(mix.validate_indices_against_gas_table());
+		assert!(!mix.is_corrupt());
+		mix.moles[4] = 5.0;
+		assert!(!mix.validate_indices_against_gas_table());
+		assert!(mix.is_corrupt());
+		mix.fix_corruption();
+		assert!(!mix.is_corrupt());
+		assert_eq!(mix.get_moles(2), 10.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_scratch_mix_reset() {
+		initialize_gases();
+		{
+			let mut scratch = ScratchMix::new();
+			scratch.set_moles(1, 40.0);
+			scratch.set_temperature(500.0);
+			scratch.volume = 100.0;
+			scratch.mark_immutable();
+		}
+		let scratch = ScratchMix::new();
+		assert_eq!(scratch.total_moles(), 0.0);
+		assert_eq!(scratch.get_temperature(), 2.7);
+		assert_eq!(scratch.volume, 2500.0);
+		assert!(!scratch.is_immutable());
+		let mut nested = ScratchMix::new();
+		nested.set_moles(0, 10.0);
+		assert_eq!(scratch.total_moles(), 0.0);
+		assert_eq!(nested.total_moles(), 10.0);
+		destroy_gas_statics();
+	}
+	#[cfg(feature = "gas_conductivity")]
+	#[test]
+	fn test_conductivity_factor() {
+		use crate::gas::types::set_gas_conductivity_manually;
+		initialize_gases();
+		set_gas_conductivity_manually(0, 2.0);
+		set_gas_conductivity_manually(1, 0.5);
+		let mut hot = Mixture::new();
+		hot.set_moles(0, 100.0);
+		hot.set_temperature(500.0);
+		let mut cold = Mixture::new();
+		cold.set_moles(1, 100.0);
+		cold.set_temperature(300.0);
+		assert!((hot.conductivity_factor(&cold) - 1.25).abs() < 0.0001);
+		assert!((cold.conductivity_factor(&hot) - 1.25).abs() < 0.0001);
+		assert_eq!(Mixture::new().conductivity_factor(&Mixture::new()), 1.0);
+		// equal heat capacities, so the delta is split evenly; 0.4 * 1.25 = 0.5, i.e. half of it
+		hot.temperature_share(&mut cold, 0.4);
+		assert!((hot.get_temperature() - 450.0).abs() < 0.01);
+		assert!((cold.get_temperature() - 350.0).abs() < 0.01);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_remove_into_returns_moles() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 30.0);
+		mix.set_moles(1, 10.0);
+		let mut into = Mixture::new();
+		assert!((mix.remove_into(20.0, &mut into) - 20.0).abs() < 0.001);
+		assert!((into.total_moles() - 20.0).abs() < 0.001);
+		assert!((mix.remove_into(100.0, &mut into) - 20.0).abs() < 0.001);
+		assert_eq!(mix.total_moles(), 0.0);
+		assert_eq!(mix.remove_into(5.0, &mut into), 0.0);
+		assert_eq!(mix.remove_ratio_into(-1.0, &mut into), 0.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_average_with() {
+		initialize_gases();
+		let mut room = Mixture::from_vol(2500.0);
+		room.set_moles(0, 100.0);
+		room.set_temperature(300.0);
+		room.mark_immutable();
+		let mut hall = Mixture::from_vol(2500.0);
+		hall.set_moles(1, 100.0);
+		hall.set_temperature(500.0);
+		let mut closet = Mixture::from_vol(5000.0);
+		closet.set_moles(1, 200.0);
+		closet.set_temperature(500.0);
+		let average = room.average_with(&[&hall, &closet]);
+		assert_eq!(average.volume, 2500.0);
+		assert!(!average.is_immutable());
+		assert!((average.get_moles(0) - 25.0).abs() < 0.001);
+		assert!((average.get_moles(1) - 75.0).abs() < 0.001);
+		assert!((average.get_temperature() - 450.0).abs() < 0.01);
+		assert_eq!(room.get_moles(0), 100.0);
+		assert_eq!(hall.get_moles(1), 100.0);
+		assert_eq!(closet.get_temperature(), 500.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_clamp_temperature() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 10.0);
+		mix.set_temperature(500.0);
+		assert_eq!(mix.clamp_temperature(273.15, 373.15), 373.15);
+		assert_eq!(mix.clamp_temperature(400.0, 1000.0), 400.0);
+		mix.set_temperature(1.0e-3);
+		assert_eq!(mix.clamp_temperature(0.0, 1000.0), TCMB);
+		assert_eq!(mix.clamp_temperature(600.0, 500.0), 600.0);
+		mix.mark_immutable();
+		assert_eq!(mix.clamp_temperature(0.0, 300.0), 600.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_try_set_moles() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		assert!(mix.try_set_moles(0, 10.0).is_ok());
+		assert_eq!(mix.get_moles(0), 10.0);
+		assert!(mix.try_set_moles(0, f32::NAN).is_err());
+		assert!(mix.try_set_moles(0, f32::INFINITY).is_err());
+		assert!(mix.try_set_moles(0, -1.0).is_err());
+		assert!(mix.try_set_moles(3, 10.0).is_err());
+		assert_eq!(mix.get_moles(0), 10.0);
+		assert!(mix.try_set_moles(0, 0.0).is_ok());
+		assert_eq!(mix.get_moles(0), 0.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_subtract() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 50.0);
+		mix.set_moles(1, 20.0);
+		mix.set_temperature(400.0);
+		let mut consumed = Mixture::new();
+		consumed.set_moles(0, 15.0);
+		consumed.set_moles(1, 30.0);
+		consumed.set_moles(2, 5.0);
+		consumed.set_temperature(1000.0);
+		mix.subtract(&consumed);
+		assert_eq!(mix.get_moles(0), 35.0);
+		assert_eq!(mix.get_moles(1), 0.0);
+		assert_eq!(mix.get_moles(2), 0.0);
+		assert_eq!(mix.total_moles(), 35.0);
+		assert_eq!(mix.get_temperature(), 400.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_copy_corrupt_into_immutable() {
+		initialize_gases();
+		let mut immutable = Mixture::new();
+		immutable.set_moles(0, 10.0);
+		immutable.set_temperature(300.0);
+		immutable.mark_immutable();
+		let mut corrupt = Mixture::new();
+		corrupt.set_moles(1, 50.0);
+		corrupt.temperature = f32::NAN;
+		assert!(corrupt.is_corrupt());
+		immutable.copy_from_mutable(&corrupt);
+		assert_eq!(immutable.get_moles(0), 10.0);
+		assert_eq!(immutable.get_moles(1), 0.0);
+		assert_eq!(immutable.get_temperature(), 300.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_gas_share_conservation() {
+		initialize_gases();
+		let mut one = Mixture::from_vol(2500.0);
+		one.set_moles(0, 80.0);
+		one.set_moles(1, 20.0);
+		one.set_temperature(400.0);
+		let mut two = Mixture::from_vol(1000.0);
+		two.set_moles(1, 60.0);
+		two.set_moles(2, 10.0);
+		two.set_temperature(250.0);
+		let initial_moles: Vec<f32> = (0..3)
+			.map(|i| one.get_moles(i) + two.get_moles(i))
+			.collect();
+		let initial_energy = one.thermal_energy() + two.thermal_energy();
+		one.gas_share(&mut two, 0.5);
+		for (i, initial) in initial_moles.into_iter().enumerate() {
+			let now = one.get_moles(i) + two.get_moles(i);
+			assert!(
+				(now - initial).abs() < 0.001,
+				"gas {} went from {} to {}",
+				i,
+				initial,
+				now
+			);
+		}
+		// halfway to equal concentrations: 80 * (1 - 2500/3500) / 2 moles of gas 0 should have moved
+		assert!((two.get_moles(0) - 80.0 * 1000.0 / 3500.0 / 2.0).abs() < 0.001);
+		let final_energy = one.thermal_energy() + two.thermal_energy();
+		assert!(
+			((final_energy - initial_energy) / initial_energy).abs() < 0.0001,
+			"energy went from {} to {}",
+			initial_energy,
+			final_energy
+		);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_split_evenly() {
+		initialize_gases();
+		let mut source = Mixture::new();
+		source.set_moles(0, 90.0);
+		source.set_moles(2, 30.0);
+		source.set_temperature(400.0);
+		let parts = source.split_evenly(3);
+		assert_eq!(parts.len(), 2);
+		assert!((source.get_moles(0) - 30.0).abs() < 0.001);
+		assert!((source.get_moles(2) - 10.0).abs() < 0.001);
+		for i in [0, 2] {
+			let total = source.get_moles(i) + parts.iter().map(|p| p.get_moles(i)).sum::<f32>();
+			let original = if i == 0 { 90.0 } else { 30.0 };
+			assert!((total - original).abs() < 0.001);
+		}
+		for part in &parts {
+			assert_eq!(part.get_temperature(), 400.0);
+			assert_eq!(part.get_moles(0), source.get_moles(0));
+		}
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_volume_at_stp() {
+		initialize_gases();
+		let mut mix = Mixture::from_vol(2500.0);
+		assert_eq!(mix.volume_at_stp(), 0.0);
+		mix.set_moles(0, MOLES_CELLSTANDARD);
+		mix.set_temperature(T20C);
+		// a standard cell is at 1 atm but 20 C, so it takes up a bit less room at 0 C
+		assert!((mix.volume_at_stp() - CELL_VOLUME * T0C / T20C).abs() < 0.01);
+		mix.set_temperature(1000.0);
+		assert!((mix.volume_at_stp() - CELL_VOLUME * T0C / T20C).abs() < 0.01);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_visibility_amount() {
+		initialize_gases();
+		let by_moles = GasVisibility::Moles(0.25);
+		let by_pressure = GasVisibility::PartialPressure(10.0);
+		let mut small = Mixture::from_vol(100.0);
+		let mut big = Mixture::from_vol(10_000.0);
+		for mix in [&mut small, &mut big] {
+			mix.set_moles(0, 1.0);
+			mix.set_temperature(T20C);
+		}
+		assert_eq!(small.visibility_amount(1.0, by_moles), 1.0);
+		assert_eq!(big.visibility_amount(1.0, by_moles), 1.0);
+		let small_pressure = small.visibility_amount(1.0, by_pressure);
+		let big_pressure = big.visibility_amount(1.0, by_pressure);
+		assert!((small_pressure - small.return_pressure()).abs() < 0.001);
+		assert!(small_pressure > by_pressure.threshold());
+		assert!(big_pressure < by_pressure.threshold());
+		// a standard cell's worth of pressure steps the same as a standard cell's worth of moles
+		assert_eq!(
+			by_pressure.step(ONE_ATMOSPHERE * 0.01),
+			by_moles.step(MOLES_CELLSTANDARD * 0.01)
+		);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_conduct_with_many() {
+		initialize_gases();
+		let energy = |mix: &Mixture| f64::from(mix.thermal_energy());
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 50.0);
+		mix.set_temperature(1000.0);
+		let mut neighbors: Vec<Mixture> = [(10.0, 300.0), (200.0, 500.0), (5.0, 2000.0)]
+			.iter()
+			.map(|&(moles, temperature)| {
+				let mut neighbor = Mixture::new();
+				neighbor.set_moles(1, moles);
+				neighbor.set_temperature(temperature);
+				neighbor
+			})
+			.collect();
+		let before = energy(&mix) + neighbors.iter().map(energy).sum::<f64>();
+		let mut pairs: Vec<(&mut Mixture, f32)> = neighbors.iter_mut().map(|n| (n, 0.4)).collect();
+		let fluxes = mix.conduct_with_many(&mut pairs);
+		assert_eq!(fluxes.len(), 3);
+		assert!(fluxes[0] > 0.0 && fluxes[1] > 0.0 && fluxes[2] < 0.0);
+		let after = energy(&mix) + neighbors.iter().map(energy).sum::<f64>();
+		assert!((before - after).abs() < before * 1e-5);
+		// every flux is the change in its neighbor's energy
+		assert!((f64::from(fluxes[0]) - (energy(&neighbors[0]) - 10.0 * 20.0 * 300.0)).abs() < 1.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_spectral_signature() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 80.0);
+		mix.set_moles(1, 20.0);
+		mix.set_temperature(300.0);
+		let mut scaled = Mixture::from_vol(10_000.0);
+		scaled.set_moles(0, 8.0);
+		scaled.set_moles(1, 2.0);
+		scaled.set_temperature(1000.0);
+		assert_eq!(mix.spectral_signature(), scaled.spectral_signature());
+		assert_eq!(mix.signature_similarity(&scaled), 1.0);
+		let mut different = Mixture::new();
+		different.set_moles(0, 50.0);
+		different.set_moles(2, 50.0);
+		assert_ne!(mix.spectral_signature(), different.spectral_signature());
+		// 50% of gas 0 overlaps with our 80%; nothing else does
+		assert!((mix.signature_similarity(&different) - 0.5).abs() < 0.001);
+		assert_eq!(Mixture::new().signature_similarity(&Mixture::new()), 1.0);
+		assert_eq!(mix.signature_similarity(&Mixture::new()), 0.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_partial_pressure() {
+		initialize_gases();
+		let mut mix = Mixture::from_vol(CELL_VOLUME);
+		mix.set_moles(0, MOLES_O2STANDARD);
+		mix.set_moles(1, MOLES_N2STANDARD);
+		mix.set_temperature(T20C);
+		assert!((mix.partial_pressure(0) - ONE_ATMOSPHERE * O2STANDARD).abs() < 0.01);
+		assert_eq!(mix.partial_pressure(2), 0.0);
+		assert_eq!(mix.partial_pressure(100), 0.0);
+		let pressures: Vec<_> = mix.partial_pressures().collect();
+		assert_eq!(pressures.len(), 2);
+		let total: f32 = pressures.iter().map(|&(_, pressure)| pressure).sum();
+		assert!((total - mix.return_pressure()).abs() < 0.01);
+		mix.volume = 0.0;
+		assert_eq!(mix.partial_pressure(0), 0.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_mole_fraction() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		assert_eq!(mix.mole_fraction(0), 0.0);
+		mix.set_moles(0, 30.0);
+		mix.set_moles(2, 10.0);
+		assert!((mix.mole_fraction(0) - 0.75).abs() < 0.0001);
+		assert!((mix.mole_fraction(2) - 0.25).abs() < 0.0001);
+		assert_eq!(mix.mole_fraction(1), 0.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_mean_molar_mass_and_density() {
+		use crate::gas::types::set_gas_molar_mass_manually;
+		initialize_gases();
+		set_gas_molar_mass_manually(0, 32.0);
+		set_gas_molar_mass_manually(1, 28.0);
+		let mut mix = Mixture::from_vol(100.0);
+		assert_eq!(mix.mean_molar_mass(), 0.0);
+		assert_eq!(mix.density(), 0.0);
+		mix.set_moles(0, 1.0);
+		mix.set_moles(1, 3.0);
+		assert!((mix.mean_molar_mass() - 29.0).abs() < 0.0001);
+		assert!((mix.density() - 1.16).abs() < 0.0001);
+		mix.volume = 0.0;
+		assert_eq!(mix.density(), 0.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_speed_of_sound() {
+		use crate::gas::types::{set_gas_adiabatic_index_manually, set_gas_molar_mass_manually};
+		initialize_gases();
+		let mut mix = Mixture::new();
+		assert_eq!(mix.speed_of_sound(), 0.0);
+		mix.set_moles(0, 10.0);
+		mix.set_temperature(T20C);
+		assert_eq!(mix.speed_of_sound(), MAX_SPEED_OF_SOUND);
+		set_gas_molar_mass_manually(0, 32.0);
+		set_gas_molar_mass_manually(1, 28.0);
+		mix.set_moles(0, 21.0);
+		mix.set_moles(1, 79.0);
+		// roughly air, at roughly 343 m/s
+		assert!((mix.speed_of_sound() - 343.0).abs() < 2.0);
+		// a monatomic gas of the same mass is faster
+		set_gas_adiabatic_index_manually(0, 5.0 / 3.0);
+		set_gas_adiabatic_index_manually(1, 5.0 / 3.0);
+		assert!(mix.speed_of_sound() > 343.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_try_set_multi() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(1, 5.0);
+		let amounts = [(0, 10.0), (2, 30.0), (1, 0.0)];
+		mix.try_set_multi(&amounts).unwrap();
+		assert_eq!(mix.get_moles(0), 10.0);
+		assert_eq!(mix.get_moles(1), 0.0);
+		assert_eq!(mix.get_moles(2), 30.0);
+		assert_eq!(mix.total_moles(), 40.0);
+		assert_eq!(mix.heat_capacity(), mix.slow_heat_capacity());
+		let before = mix.clone();
+		assert!(mix.try_set_multi(&[(0, 50.0), (1, -1.0)]).is_err());
+		assert!(mix.try_set_multi(&[(0, 50.0), (2, f32::NAN)]).is_err());
+		assert!(mix.try_set_multi(&[(0, 50.0), (100, 1.0)]).is_err());
+		assert_eq!(mix.get_moles(0), before.get_moles(0));
+		assert!(mix == before);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_scale_volume() {
+		initialize_gases();
+		let mut mix = Mixture::from_vol(1000.0);
+		mix.set_moles(0, 40.0);
+		mix.set_moles(1, 10.0);
+		mix.set_temperature(300.0);
+		let pressure = mix.return_pressure();
+		mix.scale_volume(2500.0).unwrap();
+		assert_eq!(mix.volume, 2500.0);
+		assert_eq!(mix.get_moles(0), 100.0);
+		assert_eq!(mix.get_moles(1), 25.0);
+		assert_eq!(mix.get_temperature(), 300.0);
+		assert!((mix.return_pressure() - pressure).abs() < 0.01);
+		assert!(mix.scale_volume(0.0).is_err());
+		assert!(mix.scale_volume(-10.0).is_err());
+		assert_eq!(mix.volume, 2500.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_interpolate() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 100.0);
+		mix.set_temperature(200.0);
+		let mut other = Mixture::new();
+		other.set_moles(2, 50.0);
+		other.set_temperature(400.0);
+		let original = mix.clone();
+		mix.interpolate(&other, 0.5);
+		assert_eq!(mix.get_moles(0), 50.0);
+		assert_eq!(mix.get_moles(2), 25.0);
+		assert_eq!(mix.get_temperature(), 300.0);
+		assert_eq!(mix.heat_capacity(), mix.slow_heat_capacity());
+		let mut unchanged = original.clone();
+		unchanged.interpolate(&other, -1.0);
+		assert!(unchanged == original);
+		let mut copied = original.clone();
+		copied.interpolate(&other, 2.0);
+		assert_eq!(copied.get_moles(0), 0.0);
+		assert_eq!(copied.get_moles(2), 50.0);
+		assert_eq!(copied.get_temperature(), 400.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_merge_capped() {
+		initialize_gases();
+		let mut mix = Mixture::from_vol(1000.0);
+		mix.set_moles(0, 20.0);
+		mix.set_temperature(300.0);
+		let mut giver = Mixture::from_vol(1000.0);
+		giver.set_moles(1, 100.0);
+		giver.set_temperature(600.0);
+		let mut uncapped = mix.clone();
+		assert_eq!(uncapped.merge_capped(&giver, f32::MAX), 1.0);
+		assert_eq!(uncapped.get_moles(1), 100.0);
+		let max_pressure = mix.return_pressure() * 2.0;
+		let ratio = mix.merge_capped(&giver, max_pressure);
+		assert!(ratio > 0.0 && ratio < 1.0);
+		assert!(mix.return_pressure() <= max_pressure * 1.0001);
+		assert!(mix.return_pressure() >= max_pressure * 0.999);
+		assert!((mix.get_moles(1) - 100.0 * ratio).abs() < 0.01);
+		let pressure = mix.return_pressure();
+		assert_eq!(mix.merge_capped(&giver, pressure / 2.0), 0.0);
+		assert_eq!(mix.return_pressure(), pressure);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_remove_gases_ratio() {
+		initialize_gases();
+		let mut mix = Mixture::from_vol(500.0);
+		mix.set_moles(0, 100.0);
+		mix.set_moles(1, 40.0);
+		mix.set_moles(2, 60.0);
+		mix.set_temperature(350.0);
+		let energy = mix.thermal_energy();
+		let removed = mix.remove_gases_ratio(0.5, &[1, 2]);
+		assert_eq!(removed.volume, 500.0);
+		assert_eq!(removed.get_moles(0), 0.0);
+		assert_eq!(removed.get_moles(1), 20.0);
+		assert_eq!(removed.get_moles(2), 30.0);
+		assert_eq!(mix.get_moles(0), 100.0);
+		assert_eq!(mix.get_moles(1), 20.0);
+		assert_eq!(mix.get_moles(2), 30.0);
+		assert!((removed.get_temperature() - 350.0).abs() < 0.01);
+		assert_eq!(mix.get_temperature(), 350.0);
+		assert!((mix.thermal_energy() + removed.thermal_energy() - energy).abs() < 1.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_dominant_gas() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		assert_eq!(mix.dominant_gas(), None);
+		mix.set_moles(2, 30.0);
+		mix.set_moles(0, 10.0);
+		assert_eq!(mix.dominant_gas(), Some(2));
+		mix.set_moles(1, 30.0);
+		assert_eq!(mix.dominant_gas(), Some(1));
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_gases_by_amount() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		assert!(mix.gases_by_amount().is_empty());
+		mix.set_moles(0, 10.0);
+		mix.set_moles(1, 50.0);
+		mix.set_moles(2, 10.0);
+		assert_eq!(mix.gases_by_amount(), vec![(1, 50.0), (0, 10.0), (2, 10.0)]);
+		mix.set_moles(1, GAS_MIN_MOLES / 2.0);
+		assert_eq!(mix.gases_by_amount(), vec![(0, 10.0), (2, 10.0)]);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_compare_detailed() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 10.0);
+		let mut sample = mix.clone();
+		assert_eq!(mix.compare_detailed(&sample), (0, 0.0));
+		sample.set_moles(1, 5.0);
+		sample.set_moles(2, 7.0);
+		assert_eq!(mix.compare_detailed(&sample), (2, 7.0));
+		assert_eq!(sample.compare_detailed(&mix), (2, 7.0));
+		assert_eq!(mix.compare_detailed(&sample).1, mix.compare(&sample));
+		assert_eq!(mix.gas_differences(&sample, 5.0), vec![(2, 7.0)]);
+		assert_eq!(mix.gas_differences(&mix.clone(), 0.0), vec![]);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_serialize() {
+		initialize_gases();
+		let mut mix = Mixture::from_vol(1500.0);
+		mix.set_moles(0, 12.5);
+		mix.set_moles(2, 80.0);
+		mix.set_temperature(420.0);
+		mix.set_min_heat_capacity(5.0);
+		let bytes = mix.serialize();
+		assert_eq!(bytes[0], SERIALIZATION_VERSION);
+		let loaded = Mixture::deserialize(&bytes).unwrap();
+		assert!(loaded == mix);
+		assert_eq!(loaded.volume, 1500.0);
+		assert_eq!(loaded.get_min_heat_capacity(), 5.0);
+		assert!(Mixture::deserialize(&[]).is_err());
+		assert!(Mixture::deserialize(&bytes[..bytes.len() - 1]).is_err());
+		let mut too_long = bytes.clone();
+		too_long.push(0);
+		assert!(Mixture::deserialize(&too_long).is_err());
+		let mut future = bytes.clone();
+		future[0] = SERIALIZATION_VERSION + 1;
+		assert!(Mixture::deserialize(&future).is_err());
+		let mut bad_gas = bytes.clone();
+		// the first gas's index comes right after the header and count
+		bad_gas[17..21].copy_from_slice(&3u32.to_le_bytes());
+		assert!(Mixture::deserialize(&bad_gas).is_err());
+		// the minimum heat capacity comes after the version, volume and temperature
+		for bad_min_heat_capacity in [f32::NAN, f32::INFINITY, -1.0] {
+			let mut bad_min = bytes.clone();
+			bad_min[9..13].copy_from_slice(&bad_min_heat_capacity.to_le_bytes());
+			assert!(Mixture::deserialize(&bad_min).is_err());
+		}
+		let mut huge_count = bytes.clone();
+		huge_count[13..17].copy_from_slice(&u32::MAX.to_le_bytes());
+		assert!(Mixture::deserialize(&huge_count).is_err());
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_deserialize_versions() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(1, 20.0);
+		let mut bytes = mix.serialize();
+		for version in 1..=SERIALIZATION_VERSION {
+			bytes[0] = version;
+			assert!(Mixture::deserialize(&bytes).unwrap() == mix);
+		}
+		for version in [0, SERIALIZATION_VERSION + 1, u8::MAX] {
+			bytes[0] = version;
+			assert!(Mixture::deserialize(&bytes).is_err());
+		}
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_copy_from_mutable_keeps_min_heat_capacity() {
+		initialize_gases();
+		let mut sample = Mixture::new();
+		sample.set_moles(0, 1.0);
+		// caches the sample's heat capacity, which doesn't count our minimum
+		assert_eq!(sample.heat_capacity(), 20.0);
+		let mut mix = Mixture::new();
+		mix.set_min_heat_capacity(100.0);
+		mix.copy_from_mutable(&sample);
+		assert_eq!(mix.get_min_heat_capacity(), 100.0);
+		assert_eq!(mix.heat_capacity(), 100.0);
+		assert_eq!(mix.heat_capacity(), mix.slow_heat_capacity());
+		let mut matching = Mixture::new();
+		matching.copy_from_mutable(&sample);
+		assert_eq!(matching.heat_capacity(), 20.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_no_reaction_cache_min_heat_capacity() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 10.0);
+		// as all_reactable does when nothing could react
+		mix.no_reaction_cache
+			.0
+			.store(mix.reaction_state_hash(), Relaxed);
+		mix.set_min_heat_capacity(1.0e6);
+		assert_ne!(
+			mix.no_reaction_cache.0.load(Relaxed),
+			mix.reaction_state_hash()
+		);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_min_heat_capacity_empty_mix() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_min_heat_capacity(100.0);
+		mix.set_temperature(300.0);
+		assert_eq!(mix.get_min_heat_capacity(), 100.0);
+		assert_eq!(mix.heat_capacity(), 100.0);
+		assert_eq!(mix.thermal_energy(), 30000.0);
+		mix.adjust_heat(1000.0);
+		assert!(!mix.get_temperature().is_nan());
+		assert!((mix.get_temperature() - 310.0).abs() < 0.001);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_adjust_heat_empty_mix() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_temperature(300.0);
+		mix.adjust_heat(1000.0);
+		assert_eq!(mix.get_temperature(), 300.0);
+		mix.adjust_heat(-1000.0);
+		assert_eq!(mix.get_temperature(), 300.0);
+		mix.set_moles(0, 1.0);
+		mix.adjust_heat(200.0);
+		assert!((mix.get_temperature() - 310.0).abs() < 0.001);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_adjust_heat_capped() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 10.0);
+		mix.set_temperature(300.0);
+		// 200 J/K, so 100 K of room below the ceiling is 20 kJ
+		assert_eq!(mix.adjust_heat_capped(50_000.0, 400.0), 20_000.0);
+		assert_eq!(mix.get_temperature(), 400.0);
+		assert_eq!(mix.adjust_heat_capped(1000.0, 350.0), 0.0);
+		assert_eq!(mix.get_temperature(), 400.0);
+		// removing heat isn't capped by the ceiling, but does stop at TCMB
+		let applied = mix.adjust_heat_capped(-1.0e9, 400.0);
+		assert_eq!(mix.get_temperature(), TCMB);
+		assert!((applied - (TCMB - 400.0) * 200.0).abs() < 0.1);
+		let mut empty = Mixture::new();
+		empty.set_temperature(300.0);
+		assert_eq!(empty.adjust_heat_capped(1000.0, 400.0), 0.0);
+		assert_eq!(empty.get_temperature(), 300.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_adjust_heat_clamps_to_tcmb() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 0.5);
+		mix.set_temperature(50.0);
+		mix.adjust_heat(-1.0e9);
+		assert_eq!(mix.get_temperature(), TCMB);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_multiply_respects_min_heat_capacity() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_min_heat_capacity(1000.0);
+		mix.set_moles(0, 100.0);
+		mix.set_temperature(300.0);
+		assert_eq!(mix.heat_capacity(), 2000.0);
+		mix.multiply(0.1);
+		// the floor stays put rather than scaling with the moles
+		assert_eq!(mix.heat_capacity(), 1000.0);
+		assert_eq!(mix.get_min_heat_capacity(), 1000.0);
+		assert_eq!(mix.get_temperature(), 300.0);
+		mix.multiply(20.0);
+		assert_eq!(mix.heat_capacity(), 4000.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_superconduct_conservation() {
+		initialize_gases();
+		let energy = |mix: &Mixture| f64::from(mix.thermal_energy());
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 50.0);
+		mix.set_temperature(1000.0);
+		let mut neighbors: Vec<Mixture> = [150.0, 300.0, 5000.0]
+			.iter()
+			.map(|&temp| {
+				let mut neighbor = Mixture::new();
+				neighbor.set_moles(1, 20.0);
+				neighbor.set_temperature(temp);
+				neighbor
+			})
+			.collect();
+		let turf_heat_capacity = 10000.0;
+		let turf_temperature = 293.15;
+		let before = energy(&mix)
+			+ neighbors.iter().map(energy).sum::<f64>()
+			+ f64::from(turf_heat_capacity * turf_temperature);
+		let new_turf_temperature = mix.superconduct(
+			neighbors.iter_mut().zip([0.4, 0.4, 0.2]),
+			0.5,
+			turf_heat_capacity,
+			turf_temperature,
+		);
+		assert!(new_turf_temperature > turf_temperature);
+		let after = energy(&mix)
+			+ neighbors.iter().map(energy).sum::<f64>()
+			+ f64::from(turf_heat_capacity * new_turf_temperature);
+		assert!((after - before).abs() / before < 1e-5);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_condense_below() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 20.0);
+		mix.set_moles(1, 30.0);
+		mix.set_moles(2, 40.0);
+		mix.set_temperature(100.0);
+		let removed = mix.condense_below(&[(0, 90.0), (1, 150.0), (2, 200.0), (2, 300.0)]);
+		assert_eq!(removed, vec![(1, 30.0), (2, 40.0)]);
+		assert_eq!(mix.get_moles(0), 20.0);
+		assert_eq!(mix.get_moles(1), 0.0);
+		assert_eq!(mix.get_moles(2), 0.0);
+		assert_eq!(mix.get_temperature(), 100.0);
+		assert_eq!(mix.heat_capacity(), mix.partial_heat_capacity(0));
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_copy_from_mutable_reuses_allocation() {
+		initialize_gases();
+		let mut sample = Mixture::new();
+		sample.set_moles(0, 10.0);
+		sample.set_moles(2, 30.0);
+		sample.set_temperature(500.0);
+		let mut mix = Mixture::new();
+		mix.set_moles(1, 20.0);
+		mix.copy_from_mutable(&sample);
+		assert!(mix == sample);
+		assert_eq!(mix.get_moles(1), 0.0);
+		assert_eq!(mix.total_moles(), 40.0);
+		assert_eq!(mix.heat_capacity(), sample.heat_capacity());
+		// a heap allocation survives copying a smaller mix in
+		let mut big = Mixture::new();
+		big.moles.extend_from_slice(&[1.0; 16]);
+		let capacity = big.moles.capacity();
+		big.copy_from_mutable(&sample);
+		assert!(big.moles.is_heap());
+		assert_eq!(big.moles.capacity(), capacity);
+		assert!(big == sample);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_remove_ratios_into() {
+		initialize_gases();
+		let mut source = Mixture::new();
+		source.set_moles(0, 100.0);
+		source.set_moles(1, 100.0);
+		source.set_moles(2, 100.0);
+		source.set_temperature(400.0);
+		let mut into = Mixture::new();
+		into.set_moles(0, 10.0);
+		into.set_temperature(200.0);
+		let energy_before = source.thermal_energy() + into.thermal_energy();
+		source.remove_ratios_into(&[(0, 0.5), (2, 0.25)], &mut into);
+		assert_eq!(source.get_moles(0), 50.0);
+		assert_eq!(source.get_moles(1), 100.0);
+		assert_eq!(source.get_moles(2), 75.0);
+		assert_eq!(into.get_moles(0), 60.0);
+		assert_eq!(into.get_moles(1), 0.0);
+		assert_eq!(into.get_moles(2), 25.0);
+		assert_eq!(source.get_temperature(), 400.0);
+		let energy_after = source.thermal_energy() + into.thermal_energy();
+		assert!((energy_after - energy_before).abs() / energy_before < 0.0001);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_first_difference() {
+		initialize_gases();
+		let mut expected = Mixture::new();
+		expected.set_moles(0, 10.0);
+		expected.set_moles(2, 10.0);
+		let mut actual = expected.clone();
+		assert_eq!(actual.first_difference(&expected, GAS_MIN_MOLES), None);
+		actual.set_moles(2, 12.5);
+		assert_eq!(
+			actual.first_difference(&expected, GAS_MIN_MOLES),
+			Some((2, 12.5, 10.0))
+		);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_vent_to_space() {
+		initialize_gases();
+		let mut low = Mixture::from_vol(CELL_VOLUME);
+		low.set_moles(0, MOLES_CELLSTANDARD);
+		low.set_temperature(T20C);
+		let mut high = low.clone();
+		high.multiply(9.0);
+		let low_total = low.total_moles();
+		let high_total = high.total_moles();
+		let vented_low = low.vent_to_space(0.5);
+		let vented_high = high.vent_to_space(0.5);
+		assert!((vented_low.total_moles() / low_total - 0.25).abs() < 0.001);
+		assert!((vented_high.total_moles() / high_total - 0.45).abs() < 0.001);
+		assert!((low.total_moles() + vented_low.total_moles() - low_total).abs() < 0.001);
+		assert_eq!(vented_high.get_temperature(), T20C);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_apply_reaction_effect() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 100.0);
+		mix.set_temperature(500.0);
+		let energy_before = mix.thermal_energy();
+		mix.apply_reaction_effect(&[(0, -50.0), (2, 50.0)], 10000.0);
+		assert_eq!(mix.get_moles(0), 50.0);
+		assert_eq!(mix.get_moles(2), 50.0);
+		let expected = energy_before + 10000.0;
+		assert!((mix.thermal_energy() - expected).abs() / expected < 0.0001);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_copy_from_mutable_if_changed() {
+		initialize_gases();
+		let mut sample = Mixture::new();
+		sample.set_moles(0, 10.0);
+		sample.set_moles(2, 30.0);
+		sample.set_temperature(500.0);
+		let mut archive = Mixture::new();
+		assert!(archive.copy_from_mutable_if_changed(&sample));
+		assert!(archive == sample);
+		let moles_ptr = archive.moles.as_ptr();
+		assert!(!archive.copy_from_mutable_if_changed(&sample));
+		assert_eq!(archive.moles.as_ptr(), moles_ptr);
+		sample.set_temperature(501.0);
+		assert!(archive.copy_from_mutable_if_changed(&sample));
+		assert_eq!(archive.get_temperature(), 501.0);
+		sample.set_moles(1, 5.0);
+		assert!(archive.copy_from_mutable_if_changed(&sample));
+		assert!(archive == sample);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_heat_capacity_cache() {
+		initialize_gases();
+		let assert_cache_valid = |mix: &Mixture| {
+			assert_eq!(mix.heat_capacity(), mix.slow_heat_capacity());
+		};
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 10.0);
+		mix.set_moles(1, 20.0);
+		assert_cache_valid(&mix);
+		mix.set_temperature(500.0);
+		assert_cache_valid(&mix);
+		mix.adjust_heat(10000.0);
+		assert_cache_valid(&mix);
+		let mut other = Mixture::new();
+		other.set_moles(2, 5.0);
+		other.set_temperature(100.0);
+		mix.merge(&other);
+		assert_cache_valid(&mix);
+		mix.temperature_share(&mut other, 0.5);
+		assert_cache_valid(&mix);
+		assert_cache_valid(&other);
+		mix.set_min_heat_capacity(100_000.0);
+		assert_cache_valid(&mix);
+		mix.set_min_heat_capacity(0.0);
+		assert_cache_valid(&mix);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_heat_capacity_cache_with_min_heat_capacity() {
+		initialize_gases();
+		let assert_cache_valid = |mix: &Mixture| {
+			let fresh = mix.slow_heat_capacity();
+			assert!((mix.heat_capacity() - fresh).abs() <= fresh * 0.0001);
+		};
+		let mut floored = Mixture::new();
+		floored.set_min_heat_capacity(1000.0);
+		floored.set_temperature(300.0);
+		let mut other = Mixture::new();
+		other.set_moles(0, 10.0);
+		other.set_temperature(500.0);
+		floored.merge(&other);
+		assert_cache_valid(&floored);
+		floored.multiply(0.5);
+		assert_cache_valid(&floored);
+		other.merge(&floored);
+		assert_cache_valid(&other);
+		other.merge_scaled(&floored, 0.5);
+		assert_cache_valid(&other);
+		other.multiply(3.0);
+		assert_cache_valid(&other);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_total_moles_cache() {
+		initialize_gases();
+		let assert_cache_valid = |mix: &Mixture| {
+			assert!((mix.total_moles() - mix.moles.iter().sum::<f32>()).abs() < 0.0001);
+		};
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 10.0);
+		mix.set_moles(1, 20.0);
+		assert_cache_valid(&mix);
+		mix.adjust_moles(1, -5.0);
+		assert_cache_valid(&mix);
+		let mut other = Mixture::new();
+		other.set_moles(2, 5.0);
+		mix.merge(&other);
+		assert_cache_valid(&mix);
+		mix.transfer_gases_to(0.5, &[2], &mut other);
+		assert_cache_valid(&mix);
+		assert_cache_valid(&other);
+		let removed = mix.remove_ratio(0.25);
+		assert_cache_valid(&mix);
+		assert_cache_valid(&removed);
+		mix.multiply(2.0);
+		assert_cache_valid(&mix);
+		mix.for_each_gas_mut(|_, amt| {
+			*amt += 1.0;
+			Ok(())
+		})
+		.unwrap();
+		assert_cache_valid(&mix);
+		other.copy_from_mutable(&mix);
+		assert_cache_valid(&other);
+		mix.clear();
+		assert_cache_valid(&mix);
+		destroy_gas_statics();
+	}
 }
@@ -5,7 +5,9 @@ use fxhash::FxBuildHasher;
 
 use parking_lot::{const_rwlock, RwLock};
 
-use crate::reaction::{Reaction, ReactionPriority};
+use crate::reaction::{reaction_id_from_string, Reaction, ReactionIdentifier, ReactionPriority};
+
+use float_ord::FloatOrd;
 
 use super::GasIDX;
 
@@ -14,13 +16,29 @@ use dashmap::DashMap;
 use std::{
 	cell::RefCell,
 	collections::{BTreeMap, HashMap},
-	sync::atomic::{AtomicUsize, Ordering},
+	sync::atomic::{AtomicU64, AtomicUsize, Ordering},
 };
 
+use atomic_float::AtomicF32;
+
+use super::constants::{CELL_VOLUME, MINIMUM_MOLES_DELTA_TO_MOVE, R_IDEAL_GAS_EQUATION, T20C};
+
+use super::mixture::visibility_step;
+
 static TOTAL_NUM_GASES: AtomicUsize = AtomicUsize::new(0);
 
 static REACTION_INFO: RwLock<Option<BTreeMap<ReactionPriority, Reaction>>> = const_rwlock(None);
 
+/// Bumped every time the reactions are (re)loaded, so anything cached about reactions can tell it's stale.
+static REACTION_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Priorities set from DM with `set_reaction_priority`, by reaction; reapplied whenever the reactions are reloaded.
+static REACTION_PRIORITY_OVERRIDES: RwLock<BTreeMap<ReactionIdentifier, ReactionPriority>> =
+	const_rwlock(BTreeMap::new());
+
+/// The smallest difference in moles that counts as worth sharing, settable from DM. See `minimum_moles_delta`.
+static MINIMUM_MOLES_DELTA: AtomicF32 = AtomicF32::new(MINIMUM_MOLES_DELTA_TO_MOVE);
+
 /// The temperature at which this gas can oxidize and how much fuel it can oxidize when it can.
 #[derive(Clone, Copy)]
 pub struct OxidationInfo {
@@ -105,6 +123,36 @@ pub enum FireProductInfo {
 	Plasma, // yeah, just hardcoding the funny trit production
 }
 
+/// What makes a gas visible. See `GasType::moles_visible` and `GasType::pressure_visible`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GasVisibility {
+	/// Visible once there's more than this many moles of it, however big the mix is.
+	Moles(f32),
+	/// Visible once its partial pressure is above this many kilopascals, so the same moles show up in a small mix but not a big one.
+	PartialPressure(f32),
+}
+
+impl GasVisibility {
+	/// The amount a mix needs more than to show the gas; moles or kilopascals, as appropriate.
+	#[must_use]
+	pub fn threshold(self) -> f32 {
+		match self {
+			Self::Moles(amt) | Self::PartialPressure(amt) => amt,
+		}
+	}
+	/// The overlay step for the given amount, as returned by `Mixture::visibility_amount`.
+	/// Partial pressures step as the same pressure would in a standard cell at 20 C, so they band the same as moles do there.
+	#[must_use]
+	pub fn step(self, amount: f32) -> u32 {
+		match self {
+			Self::Moles(_) => visibility_step(amount),
+			Self::PartialPressure(_) => {
+				visibility_step(amount * CELL_VOLUME / (R_IDEAL_GAS_EQUATION * T20C))
+			}
+		}
+	}
+}
+
 /// An individual gas type. Contains a whole lot of info attained from Byond when the gas is first registered.
 /// If you don't have any of these, just fork auxmos and remove them, many of these are not necessary--for example,
 /// if you don't have fusion, you can just remove `fusion_power`.
@@ -125,12 +173,30 @@ pub struct GasType {
 	/// The specific heat of the gas. Duplicated in the GAS_SPECIFIC_HEATS vector for speed.
 	/// Byond: `specific_heat`, a number.
 	pub specific_heat: f32,
+	/// Coefficients of the gas's specific heat as a polynomial in temperature, lowest order first.
+	/// Duplicated in the GAS_SPECIFIC_HEAT_POLYNOMIALS vector for speed.
+	/// Byond: `get_specific_heat_coefficients()`, a proc returning a list of numbers; if it doesn't exist, `specific_heat` is used as a constant.
+	#[cfg(feature = "variable_specific_heat")]
+	pub specific_heat_coefficients: Box<[f32]>,
 	/// Gas's fusion power. Used in fusion hooking, so this can be removed and ignored if you don't have fusion.
 	/// Byond: `fusion_power`, a number.
 	pub fusion_power: f32,
+	/// How well the gas conducts heat, relative to the flat conduction coefficient; 1 conducts exactly as without `gas_conductivity`.
+	/// Byond: `thermal_conductivity`, a number, defaulting to 1.
+	#[cfg(feature = "gas_conductivity")]
+	pub thermal_conductivity: f32,
+	/// The mass of one mole of the gas, in grams. 0 if it isn't set, in which case the gas is treated as weightless.
+	/// Byond: `molar_mass`, a number.
+	pub molar_mass: f32,
+	/// The gas's adiabatic index, i.e. its ratio of specific heats, for the speed of sound. Always above 1.
+	/// Byond: `adiabatic_index`, a number, defaulting to 1.4, as for air.
+	pub adiabatic_index: f32,
 	/// The moles at which the gas's overlay or other appearance shows up. If None, gas is never visible.
 	/// Byond: `moles_visible`, a number.
 	pub moles_visible: Option<f32>,
+	/// The partial pressure at which the gas shows up instead, in kilopascals. If set, this is used rather than `moles_visible`.
+	/// Byond: `pressure_visible`, a number.
+	pub pressure_visible: Option<f32>,
 	/// Standard enthalpy of formation.
 	/// Byond: `fire_energy_released`, a number.
 	pub enthalpy: f32,
@@ -163,10 +229,46 @@ impl GasType {
 						std::column!()
 					)
 				})?,
+			#[cfg(feature = "variable_specific_heat")]
+			specific_heat_coefficients: gas
+				.call("get_specific_heat_coefficients", &[])
+				.and_then(|coefficients| coefficients.as_list())
+				.and_then(|coefficients| {
+					(1..=coefficients.len())
+						.map(|i| coefficients.get(i).and_then(|c| c.as_number()))
+						.collect::<Result<Vec<_>, _>>()
+				})
+				.ok()
+				.filter(|coefficients| !coefficients.is_empty())
+				.map_or_else(
+					|| {
+						gas.get_number(byond_string!("specific_heat"))
+							.map(|heat| vec![heat])
+					},
+					Ok,
+				)?
+				.into_boxed_slice(),
 			fusion_power: gas
 				.get_number(byond_string!("fusion_power"))
 				.unwrap_or_default(),
+			#[cfg(feature = "gas_conductivity")]
+			thermal_conductivity: gas
+				.get_number(byond_string!("thermal_conductivity"))
+				.ok()
+				.filter(|conductivity| conductivity.is_finite() && *conductivity >= 0.0)
+				.unwrap_or(1.0),
+			molar_mass: gas
+				.get_number(byond_string!("molar_mass"))
+				.ok()
+				.filter(|mass| mass.is_finite() && *mass >= 0.0)
+				.unwrap_or_default(),
+			adiabatic_index: gas
+				.get_number(byond_string!("adiabatic_index"))
+				.ok()
+				.filter(|index| index.is_finite() && *index > 1.0)
+				.unwrap_or(1.4),
 			moles_visible: gas.get_number(byond_string!("moles_visible")).ok(),
+			pressure_visible: gas.get_number(byond_string!("pressure_visible")).ok(),
 			fire_info: {
 				if let Ok(temperature) = gas.get_number(byond_string!("oxidation_temperature")) {
 					FireInfo::Oxidation(OxidationInfo {
@@ -216,6 +318,13 @@ impl GasType {
 				.unwrap_or_default(),
 		})
 	}
+	/// What makes this gas visible, if anything: its partial pressure if it has `pressure_visible`, otherwise its moles.
+	#[must_use]
+	pub fn visibility(&self) -> Option<GasVisibility> {
+		self.pressure_visible
+			.map(GasVisibility::PartialPressure)
+			.or_else(|| self.moles_visible.map(GasVisibility::Moles))
+	}
 }
 
 static mut GAS_INFO_BY_STRING: Option<DashMap<Box<str>, GasType, FxBuildHasher>> = None;
@@ -224,6 +333,9 @@ static GAS_INFO_BY_IDX: RwLock<Option<Vec<GasType>>> = const_rwlock(None);
 
 static GAS_SPECIFIC_HEATS: RwLock<Option<Vec<f32>>> = const_rwlock(None);
 
+#[cfg(feature = "variable_specific_heat")]
+static GAS_SPECIFIC_HEAT_POLYNOMIALS: RwLock<Option<Vec<Box<[f32]>>>> = const_rwlock(None);
+
 #[init(partial)]
 fn _initialize_gas_info_structs() -> Result<(), String> {
 	unsafe {
@@ -231,6 +343,10 @@ fn _initialize_gas_info_structs() -> Result<(), String> {
 	};
 	*GAS_INFO_BY_IDX.write() = Some(Vec::new());
 	*GAS_SPECIFIC_HEATS.write() = Some(Vec::new());
+	#[cfg(feature = "variable_specific_heat")]
+	{
+		*GAS_SPECIFIC_HEAT_POLYNOMIALS.write() = Some(Vec::new());
+	}
 	Ok(())
 }
 
@@ -242,7 +358,12 @@ fn _destroy_gas_info_structs() {
 	};
 	*GAS_INFO_BY_IDX.write() = None;
 	*GAS_SPECIFIC_HEATS.write() = None;
+	#[cfg(feature = "variable_specific_heat")]
+	{
+		*GAS_SPECIFIC_HEAT_POLYNOMIALS.write() = None;
+	}
 	TOTAL_NUM_GASES.store(0, Ordering::Release);
+	REACTION_PRIORITY_OVERRIDES.write().clear();
 	CACHED_GAS_IDS.with(|gas_ids| {
 		gas_ids.borrow_mut().clear();
 	});
@@ -263,6 +384,11 @@ fn _hook_register_gas(gas: Value) {
 			let gas_cache = GasType::new(gas, old_gas.idx)?;
 			*old_gas = gas_cache.clone();
 			GAS_SPECIFIC_HEATS.write().as_mut().unwrap()[old_gas.idx] = gas_cache.specific_heat;
+			#[cfg(feature = "variable_specific_heat")]
+			{
+				GAS_SPECIFIC_HEAT_POLYNOMIALS.write().as_mut().unwrap()[old_gas.idx] =
+					gas_cache.specific_heat_coefficients.clone();
+			}
 			GAS_INFO_BY_IDX.write().as_mut().unwrap()[old_gas.idx] = gas_cache;
 		}
 		None => {
@@ -277,6 +403,12 @@ fn _hook_register_gas(gas: Value) {
 				.as_mut()
 				.unwrap()
 				.push(gas_cache.specific_heat);
+			#[cfg(feature = "variable_specific_heat")]
+			GAS_SPECIFIC_HEAT_POLYNOMIALS
+				.write()
+				.as_mut()
+				.unwrap()
+				.push(gas_cache.specific_heat_coefficients.clone());
 			GAS_INFO_BY_IDX.write().as_mut().unwrap().push(gas_cache);
 			CACHED_IDX_TO_STRINGS.with(|gas_ids| {
 				let mut map = gas_ids.borrow_mut();
@@ -288,6 +420,29 @@ fn _hook_register_gas(gas: Value) {
 	Ok(Value::null())
 }
 
+/// Args: (delta). Sets the smallest difference in moles that gas mixtures and turfs will bother sharing over.
+/// Higher values let the air settle sooner for less CPU, lower ones keep it more accurate. Null resets it to the default.
+#[hook("/proc/set_minimum_moles_delta")]
+fn _hook_set_minimum_moles_delta(delta: Value) {
+	let delta = if delta.is_null() {
+		MINIMUM_MOLES_DELTA_TO_MOVE
+	} else {
+		delta.as_number().map_err(|_| {
+			runtime!(
+				"Attempt to interpret non-number value as number {} {}:{}",
+				std::file!(),
+				std::line!(),
+				std::column!()
+			)
+		})?
+	};
+	if !delta.is_finite() || delta < 0.0 {
+		return Err(runtime!("Invalid minimum moles delta: {}", delta));
+	}
+	MINIMUM_MOLES_DELTA.store(delta, Ordering::Relaxed);
+	Ok(Value::null())
+}
+
 #[hook("/proc/auxtools_atmos_init")]
 fn _hook_init() {
 	let data = Value::globals()
@@ -301,6 +456,7 @@ fn _hook_init() {
 		)?;
 	}
 	*REACTION_INFO.write() = Some(get_reaction_info());
+	REACTION_GENERATION.fetch_add(1, Ordering::Release);
 	Ok(Value::from(true))
 }
 
@@ -332,48 +488,172 @@ fn get_reaction_info() -> BTreeMap<ReactionPriority, Reaction> {
 			}
 		}
 	}
+	// overrides for reactions that have since been removed just don't apply
+	for (&id, &priority) in REACTION_PRIORITY_OVERRIDES.read().iter() {
+		if let Err(runtime) = reprioritize_reaction(&mut reaction_cache, id, priority) {
+			drop(sender.try_send(Box::new(move || Err(runtime))));
+		}
+	}
 	reaction_cache
 }
 
+/// Moves the reaction with the given ID to the given priority, re-sorting it among the others.
+/// Returns false if there's no such reaction.
+/// # Errors
+/// If another reaction already has that priority.
+pub(crate) fn reprioritize_reaction(
+	reactions: &mut BTreeMap<ReactionPriority, Reaction>,
+	id: ReactionIdentifier,
+	priority: ReactionPriority,
+) -> Result<bool, Runtime> {
+	let old_priority = match reactions.values().find(|reaction| reaction.get_id() == id) {
+		Some(reaction) => reaction.get_priority(),
+		None => return Ok(false),
+	};
+	if old_priority == priority {
+		return Ok(true);
+	}
+	if reactions.contains_key(&priority) {
+		return Err(runtime!(
+			"Can't move reaction {} to priority {}, another reaction already has it",
+			id,
+			priority.0
+		));
+	}
+	let mut reaction = reactions.remove(&old_priority).unwrap();
+	reaction.set_priority(priority);
+	reactions.insert(priority, reaction);
+	Ok(true)
+}
+
+/// Swaps the new reactions in, unless they're the same as the current ones. Returns whether they were swapped in.
+pub(crate) fn replace_reactions(
+	current: &mut Option<BTreeMap<ReactionPriority, Reaction>>,
+	new: BTreeMap<ReactionPriority, Reaction>,
+) -> bool {
+	if current.as_ref() == Some(&new) {
+		return false;
+	}
+	*current = Some(new);
+	true
+}
+
 #[hook("/datum/controller/subsystem/air/proc/auxtools_update_reactions")]
 fn _update_reactions() {
-	*REACTION_INFO.write() = Some(get_reaction_info());
+	// reloading the same reactions shouldn't throw away everything cached about them
+	if replace_reactions(&mut REACTION_INFO.write(), get_reaction_info()) {
+		REACTION_GENERATION.fetch_add(1, Ordering::Release);
+	}
+	Ok(Value::from(true))
+}
+
+/// Args: (reaction_id, priority). Moves the reaction with the given string ID to the given priority, changing the order it fires in relative to the others.
+/// Lasts until shutdown, including through reloads of the reactions, as long as the reaction is still there and nothing else has taken the priority.
+#[hook("/datum/controller/subsystem/air/proc/set_reaction_priority")]
+fn _set_reaction_priority(reaction_id: Value, priority_val: Value) {
+	let string_id = reaction_id.as_string()?;
+	let priority = priority_val
+		.as_number()
+		.ok()
+		.filter(|priority| priority.is_finite())
+		.ok_or_else(|| runtime!("Reaction priority must be a finite number!"))?;
+	let id = reaction_id_from_string(&string_id);
+	{
+		let mut reaction_info = REACTION_INFO.write();
+		let reactions = reaction_info
+			.as_mut()
+			.ok_or_else(|| runtime!("Reactions not loaded yet! Uh oh!"))?;
+		if !reprioritize_reaction(reactions, id, FloatOrd(priority))? {
+			return Err(runtime!("No reaction with id {}", string_id));
+		}
+	}
+	REACTION_PRIORITY_OVERRIDES
+		.write()
+		.insert(id, FloatOrd(priority));
+	REACTION_GENERATION.fetch_add(1, Ordering::Release);
 	Ok(Value::from(true))
 }
 
 /// Calls the given closure with all reaction info as an argument.
-/// # Panics
+/// # Errors
 /// If reactions aren't loaded yet.
-pub fn with_reactions<T, F>(mut f: F) -> T
+pub fn with_reactions<T, F>(mut f: F) -> Result<T, Runtime>
 where
 	F: FnMut(&BTreeMap<ReactionPriority, Reaction>) -> T,
 {
-	f(REACTION_INFO
-		.read()
-		.as_ref()
-		.unwrap_or_else(|| panic!("Reactions not loaded yet! Uh oh!")))
+	Ok(f(REACTION_INFO.read().as_ref().ok_or_else(|| {
+		runtime!("Reactions not loaded yet! Uh oh!")
+	})?))
+}
+
+/// Returns a number that changes whenever the reactions are reloaded.
+pub fn reaction_generation() -> u64 {
+	REACTION_GENERATION.load(Ordering::Acquire)
 }
 
 /// Runs the given closure with the global specific heats vector locked.
 /// # Panics
 /// If gas info isn't loaded yet.
+#[cfg_attr(feature = "variable_specific_heat", allow(dead_code))]
 pub fn with_specific_heats<T>(f: impl FnOnce(&[f32]) -> T) -> T {
 	f(GAS_SPECIFIC_HEATS.read().as_ref().unwrap().as_slice())
 }
 
-/// Gets the fusion power of the given gas.
+/// Gets the specific heat of the given gas.
+/// # Errors
+/// If gas info isn't loaded yet, or there's no gas with the given index.
+pub fn gas_specific_heat(idx: GasIDX) -> Result<f32, Runtime> {
+	GAS_SPECIFIC_HEATS
+		.read()
+		.as_ref()
+		.ok_or_else(|| runtime!("Gases not loaded yet! Uh oh!"))?
+		.get(idx)
+		.copied()
+		.ok_or_else(|| runtime!("Invalid gas index: {}", idx))
+}
+
+/// Runs the given closure with the global specific heat polynomials vector locked.
+/// Each polynomial is a slice of coefficients, lowest order first; evaluate them with `evaluate_specific_heat`.
 /// # Panics
 /// If gas info isn't loaded yet.
-#[cfg(feature = "reaction_hooks")]
+#[cfg(feature = "variable_specific_heat")]
+pub fn with_specific_heat_polynomials<T>(f: impl FnOnce(&[Box<[f32]>]) -> T) -> T {
+	f(GAS_SPECIFIC_HEAT_POLYNOMIALS
+		.read()
+		.as_ref()
+		.unwrap()
+		.as_slice())
+}
+
+/// Evaluates a specific heat polynomial, as given by `with_specific_heat_polynomials`, at the given temperature.
+#[cfg(feature = "variable_specific_heat")]
 #[must_use]
-pub fn gas_fusion_power(idx: &GasIDX) -> f32 {
-	GAS_INFO_BY_IDX
+pub fn evaluate_specific_heat(coefficients: &[f32], temperature: f32) -> f32 {
+	coefficients
+		.iter()
+		.rev()
+		.fold(0.0, |acc, &c| acc.mul_add(temperature, c))
+}
+
+/// Gets the fusion power of the given gas.
+/// # Errors
+/// If gas info isn't loaded yet, or there's no gas with the given index.
+#[cfg(feature = "reaction_hooks")]
+pub fn gas_fusion_power(idx: &GasIDX) -> Result<f32, Runtime> {
+	Ok(GAS_INFO_BY_IDX
 		.read()
 		.as_ref()
-		.unwrap_or_else(|| panic!("Gases not loaded yet! Uh oh!"))
+		.ok_or_else(|| runtime!("Gases not loaded yet! Uh oh!"))?
 		.get(*idx as usize)
-		.unwrap()
-		.fusion_power
+		.ok_or_else(|| runtime!("Invalid gas index: {}", idx))?
+		.fusion_power)
+}
+
+/// Returns the smallest difference in moles between two mixtures that's worth sharing over.
+/// Defaults to `MINIMUM_MOLES_DELTA_TO_MOVE`; servers can change it with `/proc/set_minimum_moles_delta`.
+/// Read it when it's needed rather than holding on to it, so changes take effect right away.
+pub fn minimum_moles_delta() -> f32 {
+	MINIMUM_MOLES_DELTA.load(Ordering::Relaxed)
 }
 
 /// Returns the total number of gases in use. Only used by gas mixtures; should probably stay that way.
@@ -381,31 +661,30 @@ pub fn total_num_gases() -> GasIDX {
 	TOTAL_NUM_GASES.load(Ordering::Acquire)
 }
 
-/// Gets the gas visibility threshold for the given gas ID.
-/// # Panics
-/// If gas info isn't loaded yet.
-#[must_use]
-pub fn gas_visibility(idx: usize) -> Option<f32> {
-	GAS_INFO_BY_IDX
+/// Gets the gas visibility threshold for the given gas ID, or `None` if the gas is never visible.
+/// # Errors
+/// If gas info isn't loaded yet, or there's no gas with the given index.
+pub fn gas_visibility(idx: usize) -> Result<Option<GasVisibility>, Runtime> {
+	Ok(GAS_INFO_BY_IDX
 		.read()
 		.as_ref()
-		.unwrap_or_else(|| panic!("Gases not loaded yet! Uh oh!"))
+		.ok_or_else(|| runtime!("Gases not loaded yet! Uh oh!"))?
 		.get(idx as usize)
-		.unwrap()
-		.moles_visible
+		.ok_or_else(|| runtime!("Invalid gas index: {}", idx))?
+		.visibility())
 }
 
 /// Gets a copy of all the gas visibilities.
 /// # Panics
 /// If gas info isn't loaded yet.
 #[must_use]
-pub fn visibility_copies() -> Box<[Option<f32>]> {
+pub fn visibility_copies() -> Box<[Option<GasVisibility>]> {
 	GAS_INFO_BY_IDX
 		.read()
 		.as_ref()
 		.unwrap_or_else(|| panic!("Gases not loaded yet! Uh oh!"))
 		.iter()
-		.map(|g| g.moles_visible)
+		.map(GasType::visibility)
 		.collect::<Vec<_>>()
 		.into_boxed_slice()
 }
@@ -478,15 +757,15 @@ pub fn gas_idx_from_value(string_val: &Value) -> Result<GasIDX, Runtime> {
 }
 
 /// Takes an index and returns a borrowed string representing the string ID of the gas datum stored in that index.
-/// # Panics
-/// If an invalid gas index is given to this. This should never happen, so we panic instead of runtiming.
+/// # Errors
+/// If an invalid gas index is given to this.
 pub fn gas_idx_to_id(idx: GasIDX) -> DMResult {
 	CACHED_IDX_TO_STRINGS.with(|thin| {
 		let stuff = thin.borrow();
 		Value::from_string(
 			stuff
 				.get(&idx)
-				.unwrap_or_else(|| panic!("Invalid gas index: {}", idx)),
+				.ok_or_else(|| runtime!("Invalid gas index: {}", idx))?,
 		)
 	})
 }
@@ -499,8 +778,15 @@ pub fn register_gas_manually(gas_id: &'static str, specific_heat: f32) {
 		name: gas_id.into(),
 		flags: 0,
 		specific_heat,
+		#[cfg(feature = "variable_specific_heat")]
+		specific_heat_coefficients: Box::new([specific_heat]),
 		fusion_power: 0.0,
+		#[cfg(feature = "gas_conductivity")]
+		thermal_conductivity: 1.0,
+		molar_mass: 0.0,
+		adiabatic_index: 1.4,
 		moles_visible: None,
+		pressure_visible: None,
 		enthalpy: 0.0,
 		fire_radiation_released: 0.0,
 		fire_info: FireInfo::None,
@@ -516,6 +802,12 @@ pub fn register_gas_manually(gas_id: &'static str, specific_heat: f32) {
 		.as_mut()
 		.unwrap()
 		.push(gas_cache.specific_heat);
+	#[cfg(feature = "variable_specific_heat")]
+	GAS_SPECIFIC_HEAT_POLYNOMIALS
+		.write()
+		.as_mut()
+		.unwrap()
+		.push(gas_cache.specific_heat_coefficients.clone());
 	GAS_INFO_BY_IDX.write().as_mut().unwrap().push(gas_cache);
 	CACHED_IDX_TO_STRINGS.with(|gas_ids| {
 		let mut map = gas_ids.borrow_mut();
@@ -524,6 +816,21 @@ pub fn register_gas_manually(gas_id: &'static str, specific_heat: f32) {
 	TOTAL_NUM_GASES.fetch_add(1, Ordering::Release); // this is the only thing that stores it other than shutdown
 }
 
+#[cfg(all(test, feature = "gas_conductivity"))]
+pub fn set_gas_conductivity_manually(idx: GasIDX, thermal_conductivity: f32) {
+	GAS_INFO_BY_IDX.write().as_mut().unwrap()[idx].thermal_conductivity = thermal_conductivity;
+}
+
+#[cfg(test)]
+pub fn set_gas_molar_mass_manually(idx: GasIDX, molar_mass: f32) {
+	GAS_INFO_BY_IDX.write().as_mut().unwrap()[idx].molar_mass = molar_mass;
+}
+
+#[cfg(test)]
+pub fn set_gas_adiabatic_index_manually(idx: GasIDX, adiabatic_index: f32) {
+	GAS_INFO_BY_IDX.write().as_mut().unwrap()[idx].adiabatic_index = adiabatic_index;
+}
+
 #[cfg(test)]
 pub fn set_gas_statics_manually() {
 	_initialize_gas_info_structs().unwrap();
@@ -533,3 +840,27 @@ pub fn set_gas_statics_manually() {
 pub fn destroy_gas_statics() {
 	_destroy_gas_info_structs();
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_invalid_gas_index_errors() {
+		set_gas_statics_manually();
+		assert!(gas_visibility(0).is_err());
+		assert!(gas_specific_heat(0).is_err());
+		register_gas_manually("o2", 20.0);
+		assert_eq!(gas_visibility(0).unwrap(), None);
+		assert_eq!(gas_specific_heat(0).unwrap(), 20.0);
+		assert!(gas_visibility(1).is_err());
+		assert!(gas_specific_heat(1).is_err());
+		assert!(gas_idx_to_id(1).is_err());
+		destroy_gas_statics();
+	}
+
+	#[test]
+	fn test_reactions_not_loaded_errors() {
+		assert!(with_reactions(|reactions| reactions.len()).is_err());
+	}
+}
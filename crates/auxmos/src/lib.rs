@@ -7,19 +7,25 @@ mod reaction;
 
 mod parser;
 
-use auxtools::{byond_string, hook, inventory, runtime, List, Value};
+#[allow(dead_code)]
+mod rng;
+
+use auxtools::{byond_string, hook, inventory, runtime, DMResult, List, Proc, Value};
 
 use auxcleanup::{datum_del, DelDatumFunc};
 
 use gas::{
-	amt_gases, constants, gas_idx_from_string, gas_idx_from_value, gas_idx_to_id, tot_gases, types,
-	with_gas_info, with_mix, with_mix_mut, with_mixes, with_mixes_custom, with_mixes_mut, GasArena,
-	Mixture,
+	amt_gases, constants, export_all_gas_state, gas_count_trend, gas_idx_from_string,
+	gas_idx_from_value, gas_idx_to_id, gas_memory_estimate, gas_specific_heat,
+	global_thermal_energy, minimum_moles_delta, mix_index, new_gas_mixture, registered_gas_mixture,
+	registered_gas_mixtures, sample_gas_count, smoothed_temperature, tot_gases, total_num_gases,
+	types, validate_all_gas_mixtures, with_gas_info, with_mix, with_mix_indexed, with_mix_mut,
+	with_mixes, with_mixes_custom, with_mixes_mut, GasArena, Mixture,
 };
 
-use reaction::react_by_id;
+use reaction::{react_by_id, react_guarded, reaction_name, ReactionIdentifier};
 
-use gas::constants::{ReactionReturn, GAS_MIN_MOLES, MINIMUM_MOLES_DELTA_TO_MOVE};
+use gas::constants::{ReactionReturn, GAS_MIN_MOLES};
 
 /// Args: (ms). Runs callbacks until time limit is reached. If time limit is omitted, runs all callbacks.
 #[hook("/proc/process_atmos_callbacks")]
@@ -33,6 +39,48 @@ fn _register_gasmixture_hook() {
 	gas::GasArena::register_mix(src)
 }
 
+/// Returns: true if this gas mixture is still registered and has its own slot in the arena, false if it's been unregistered,
+/// in which case using it will either error or affect some other mixture that's been given its slot.
+#[hook("/datum/gas_mixture/proc/is_registered")]
+fn _is_registered_hook() {
+	Ok(Value::from(gas::is_live_gas_mixture(src)))
+}
+
+/// Takes a snapshot of the gas mixture, which can be put back with restore_gas_snapshot, e.g. to undo a tick.
+/// Snapshots are kept until freed with free_gas_snapshot.
+/// Returns: the snapshot's handle.
+#[hook("/datum/gas_mixture/proc/snapshot")]
+fn _snapshot_hook() {
+	Ok(Value::from(gas::GasArena::snapshot(mix_index(src)?)? as f32))
+}
+
+/// Args: (mixture, handle). Puts the gases and temperature from the snapshot with the given handle back into the mixture. Runtimes if there's no such snapshot.
+#[hook("/proc/restore_gas_snapshot")]
+fn _restore_gas_snapshot_hook(mix: Value, handle: Value) {
+	let handle = snapshot_handle(handle)?;
+	gas::GasArena::restore(mix_index(mix)?, handle)?;
+	Ok(Value::null())
+}
+
+/// Args: (handle). Frees the snapshot with the given handle, which may then be given to a new snapshot.
+/// Returns: true if there was such a snapshot, false otherwise.
+#[hook("/proc/free_gas_snapshot")]
+fn _free_gas_snapshot_hook(handle: Value) {
+	let handle = snapshot_handle(handle)?;
+	Ok(Value::from(gas::GasArena::free_snapshot(handle)))
+}
+
+fn snapshot_handle(handle: &Value) -> Result<usize, auxtools::Runtime> {
+	handle.as_number().map(|n| n as usize).map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})
+}
+
 /// Adds the gas mixture's ID to the queue of mixtures that have been deleted, to be reused later.
 /// This version is only if auxcleanup is not being used; it should be called from /datum/gas_mixture/Del.
 #[cfg(not(feature = "auxcleanup_gas_deletion"))]
@@ -65,6 +113,12 @@ fn _min_heat_cap_hook(arg_min: Value) {
 	})
 }
 
+/// Returns: the mix's minimum heat capacity.
+#[hook("/datum/gas_mixture/proc/get_min_heat_capacity")]
+fn _get_min_heat_cap_hook() {
+	with_mix(src, |mix| Ok(Value::from(mix.get_min_heat_capacity())))
+}
+
 /// Returns: Amount of substance, in moles.
 #[hook("/datum/gas_mixture/proc/total_moles")]
 fn _total_moles_hook() {
@@ -77,12 +131,86 @@ fn _return_pressure_hook() {
 	with_mix(src, |mix| Ok(Value::from(mix.return_pressure())))
 }
 
+/// Returns: the mix's total moles divided by its volume, or 0 if it has no volume.
+#[hook("/datum/gas_mixture/proc/moles_per_liter")]
+fn _moles_per_liter_hook() {
+	with_mix(src, |mix| Ok(Value::from(mix.moles_per_liter())))
+}
+
+/// Returns: the volume the mix's gas would take up at 0 C and 1 atm, in liters, for comparing mixes at different temperatures. 0 if empty.
+#[hook("/datum/gas_mixture/proc/volume_at_stp")]
+fn _volume_at_stp_hook() {
+	with_mix(src, |mix| Ok(Value::from(mix.volume_at_stp())))
+}
+
+/// Returns: the mole-weighted average molar mass of the mix's gases, in grams per mole, going by each gas's molar_mass. 0 if empty.
+#[hook("/datum/gas_mixture/proc/mean_molar_mass")]
+fn _mean_molar_mass_hook() {
+	with_mix(src, |mix| Ok(Value::from(mix.mean_molar_mass())))
+}
+
+/// Returns: the mix's density, in grams per liter, going by each gas's molar_mass. 0 if empty or volumeless.
+#[hook("/datum/gas_mixture/proc/density")]
+fn _density_hook() {
+	with_mix(src, |mix| Ok(Value::from(mix.density())))
+}
+
+/// Returns: the speed of sound in the mix, in meters per second, going by each gas's molar_mass and adiabatic_index. 0 in a vacuum.
+#[hook("/datum/gas_mixture/proc/speed_of_sound")]
+fn _speed_of_sound_hook() {
+	with_mix(src, |mix| Ok(Value::from(mix.speed_of_sound())))
+}
+
+/// Args: (max_pressure). Returns: how many moles can be added to the mix, at its current temperature, before it reaches the given pressure in kilopascals. Never negative.
+#[hook("/datum/gas_mixture/proc/moles_until_pressure")]
+fn _moles_until_pressure_hook(pressure_arg: Value) {
+	let max_pressure = pressure_arg.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mix(src, |mix| {
+		Ok(Value::from(mix.moles_until_pressure(max_pressure)))
+	})
+}
+
 /// Returns: the mix's temperature, in kelvins.
 #[hook("/datum/gas_mixture/proc/return_temperature")]
 fn _return_temperature_hook() {
 	with_mix(src, |mix| Ok(Value::from(mix.get_temperature())))
 }
 
+/// Returns: the mix's temperature, in kelvins, clamped to TCMB for UIs. Use `return_temperature` for anything else.
+#[hook("/datum/gas_mixture/proc/return_temperature_display")]
+fn _return_temperature_display_hook() {
+	with_mix(src, |mix| Ok(Value::from(mix.get_temperature_display())))
+}
+
+/// Args: (alpha). How much the current temperature counts towards the smoothed one, between 0 and 1; lower is smoother but slower to follow.
+/// Keeps an exponential moving average of the temperature each time it's called, so readouts polled every tick don't flicker. Display only.
+/// Returns: the smoothed temperature, in kelvins, clamped to TCMB like `return_temperature_display`.
+#[hook("/datum/gas_mixture/proc/return_temperature_smoothed")]
+fn _return_temperature_smoothed_hook(alpha: Value) {
+	let alpha = alpha.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mix_indexed(src, |mix_id, mix| {
+		Ok(Value::from(smoothed_temperature(
+			mix_id,
+			mix.get_temperature_display(),
+			alpha,
+		)))
+	})
+}
+
 /// Returns: the mix's volume, in liters.
 #[hook("/datum/gas_mixture/proc/return_volume")]
 fn _return_volume_hook() {
@@ -104,6 +232,44 @@ fn _merge_hook(giver: Value) {
 	})
 }
 
+/// Args: (mixture, t). Blends src towards the argument mixture by t, between 0 and 1: each gas and the temperature
+/// move linearly from src's to the argument's. Unlike merge, nothing is added together.
+#[hook("/datum/gas_mixture/proc/interpolate_from")]
+fn _interpolate_from_hook(other: Value, t_arg: Value) {
+	let t = t_arg.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mixes_custom(src, other, |src_mix, other_mix| {
+		src_mix.write().interpolate(&other_mix.read(), t);
+		Ok(Value::null())
+	})
+}
+
+/// Args: (giver, max_pressure). Merges as much of the giver into src as keeps src at or below max_pressure, in kilopascals.
+/// The giver isn't changed. Returns: the ratio of the giver that was merged, from 0 to 1, so the rest can be left in it.
+#[hook("/datum/gas_mixture/proc/merge_capped")]
+fn _merge_capped_hook(giver: Value, max_pressure: Value) {
+	let max_pressure = max_pressure.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mixes_custom(src, giver, |src_mix, giver_mix| {
+		let giver = giver_mix.read();
+		Ok(Value::from(
+			src_mix.write().merge_capped(&giver, max_pressure),
+		))
+	})
+}
+
 /// Args: (mixture, ratio). Takes the given ratio of gas from src and puts it into the argument mixture. Ratio is a number between 0 and 1.
 #[hook("/datum/gas_mixture/proc/__remove_ratio")]
 fn _remove_ratio_hook(into: Value, ratio_arg: Value) {
@@ -114,13 +280,80 @@ fn _remove_ratio_hook(into: Value, ratio_arg: Value) {
 	})
 }
 
+/// Args: (mixture, hole_ratio). Vents some of src into space through a hole, merging what was vented into the argument mixture, on top of anything already in it.
+/// hole_ratio is the most that can vent at once, between 0 and 1; the higher src's pressure, the closer it gets to that.
+/// Runtimes, venting nothing, if the argument mixture is immutable, since the vented gas would have nowhere to go.
+#[hook("/datum/gas_mixture/proc/vent_to_space")]
+fn _vent_to_space_hook(into: Value, hole_ratio: Value) {
+	let hole_ratio = hole_ratio.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mixes_mut(src, into, |src_mix, into_mix| {
+		if into_mix.is_immutable() {
+			return Err(runtime!("Attempted to vent into an immutable gas mixture"));
+		}
+		into_mix.merge(&src_mix.vent_to_space(hole_ratio));
+		Ok(Value::null())
+	})
+}
+
+/// Args: (n). Splits src into n equal parts, keeping one and putting each of the others into a new gas mixture.
+/// Each part has an equal share of src's gases, at src's temperature and volume.
+/// n must be a whole number from 1 to 256; anything else runtimes without changing src.
+/// Requires `/proc/auxtools_new_gas_mixture`, which should return a new `/datum/gas_mixture`.
+/// Returns: a list of the n - 1 new gas mixtures.
+#[hook("/datum/gas_mixture/proc/split_evenly")]
+fn _split_evenly_hook(n_arg: Value) {
+	// every part but src's is a new datum, so this stops a bad n from making them until the server falls over
+	const MAX_PARTS: usize = 256;
+	let n = n_arg.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	if !n.is_finite() || n.fract() != 0.0 || n < 1.0 || n > MAX_PARTS as f32 {
+		return Err(runtime!(
+			"Can't split a gas mixture into {} parts; it must be a whole number from 1 to {}",
+			n,
+			MAX_PARTS
+		));
+	}
+	let n = n as usize;
+	// all made and resolved before src is split, so a failure here can't lose any of its gas
+	let outputs = (1..n)
+		.map(|_| {
+			let output = new_gas_mixture()?;
+			let idx = mix_index(&output)?;
+			Ok((output, idx))
+		})
+		.collect::<Result<Vec<_>, auxtools::Runtime>>()?;
+	let parts = with_mix_mut(src, |mix| Ok(mix.split_evenly(n)))?;
+	let output_list: List = List::new();
+	for ((output, idx), part) in outputs.into_iter().zip(parts) {
+		gas::GasArena::with_gas_mixture_mut(idx, |mix| {
+			*mix = part;
+			Ok(())
+		})?;
+		output_list.append(output);
+	}
+	Ok(Value::from(output_list))
+}
+
 /// Args: (mixture, amount). Takes the given amount of gas from src and puts it into the argument mixture. Amount is amount of substance in moles.
+/// Returns: the moles actually taken, which is less than the amount if src didn't have that much.
 #[hook("/datum/gas_mixture/proc/__remove")]
 fn _remove_hook(into: Value, amount_arg: Value) {
 	let amount = amount_arg.as_number().unwrap_or_default();
 	with_mixes_mut(src, into, |src_mix, into_mix| {
-		src_mix.remove_into(amount, into_mix);
-		Ok(Value::null())
+		Ok(Value::from(src_mix.remove_into(amount, into_mix)))
 	})
 }
 
@@ -155,6 +388,223 @@ fn _temperature_share_hook() {
 	}
 }
 
+/// Args: (sink_temperature, coefficient). Moves the mix's temperature `coefficient` of the way towards sink_temperature,
+/// as if conducting into something with infinite thermal mass, like a wall or space.
+/// Returns: the heat that went into the mix, in joules; negative if it lost heat.
+#[hook("/datum/gas_mixture/proc/conduct_to_sink")]
+fn _conduct_to_sink_hook(sink_temperature: Value, coefficient: Value) {
+	let sink_temperature = sink_temperature.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let coefficient = coefficient.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mix_mut(src, |mix| {
+		Ok(Value::from(
+			mix.conduct_to_sink(sink_temperature, coefficient),
+		))
+	})
+}
+
+/// Args: (neighbor_list, coefficients_list, turf_heat_capacity, turf_temperature). Conducts heat between src, a turf with the given heat capacity and temperature,
+/// and every mixture in neighbor_list, conserving energy across the whole group. coefficients_list has the conduction coefficient for each neighbor, in the same order;
+/// an extra entry at the end is used for the turf, which otherwise fully equalizes with src. Runtimes if the lists don't line up, or on any entry of the wrong type.
+/// Returns: the turf's new temperature.
+#[hook("/datum/gas_mixture/proc/superconduct")]
+fn _superconduct_hook(
+	neighbor_list: Value,
+	coefficients_list: Value,
+	turf_heat_capacity: Value,
+	turf_temperature: Value,
+) {
+	use std::collections::BTreeMap;
+	let neighbors = neighbor_list.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let coefficients = coefficients_list.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let turf_heat_capacity = turf_heat_capacity.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let turf_temperature = turf_temperature.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	if coefficients.len() != neighbors.len() && coefficients.len() != neighbors.len() + 1 {
+		return Err(runtime!(
+			"Incorrect list length for superconduct ({} neighbors, but {} coefficients).",
+			neighbors.len(),
+			coefficients.len()
+		));
+	}
+	let mix_id = mix_index(src)?;
+	let turf_coefficient = if coefficients.len() > neighbors.len() {
+		coefficients
+			.get(neighbors.len() + 1)?
+			.as_number()
+			.map_err(|_| runtime!("The turf coefficient given to superconduct isn't a number"))?
+	} else {
+		constants::MAX_CONDUCTION_COEFFICIENT
+	};
+	let mut neighbor_coefficients: BTreeMap<usize, f32> = BTreeMap::new();
+	for i in 1..=neighbors.len() {
+		let idx = mix_index(&neighbors.get(i)?)
+			.map_err(|_| runtime!("Entry {} of the neighbor list isn't a gas mixture", i))?;
+		let coefficient = coefficients
+			.get(i)?
+			.as_number()
+			.map_err(|_| runtime!("Entry {} of the coefficient list isn't a number", i))?;
+		// keyed by ID so that each neighbor gets locked only once, and never src
+		if idx != mix_id {
+			neighbor_coefficients.insert(idx, coefficient);
+		}
+	}
+	GasArena::with_all_mixtures(|all_mixtures| {
+		let mut mix = all_mixtures
+			.get(mix_id)
+			.ok_or_else(|| runtime!("No gas mixture with ID {} exists!", mix_id))?
+			.write();
+		let mut neighbor_mixes = neighbor_coefficients
+			.iter()
+			.map(|(&id, &coefficient)| {
+				all_mixtures
+					.get(id)
+					.map(|lock| (lock.write(), coefficient))
+					.ok_or_else(|| runtime!("No gas mixture with ID {} exists!", id))
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		let new_turf_temperature = mix.superconduct(
+			neighbor_mixes
+				.iter_mut()
+				.map(|(neighbor, coefficient)| (&mut **neighbor, *coefficient)),
+			turf_coefficient,
+			turf_heat_capacity,
+			turf_temperature,
+		);
+		Ok(Value::from(new_turf_temperature))
+	})
+}
+
+/// Args: (neighbor_list, coefficients_list). Conducts heat between src and every mixture in neighbor_list at once, conserving energy across the group.
+/// coefficients_list has the conduction coefficient for each neighbor, in the same order. Neighbors listed more than once only conduct once, with the last coefficient given.
+/// Runtimes if the lists aren't the same length, or on any entry of the wrong type.
+/// Returns: an associative list of each neighbor to the heat that went into it, in joules; negative if it went into src instead.
+#[hook("/datum/gas_mixture/proc/conduct_with_many")]
+fn _conduct_with_many_hook(neighbor_list: Value, coefficients_list: Value) {
+	use std::collections::BTreeMap;
+	let neighbors = neighbor_list.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let coefficients = coefficients_list.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	if coefficients.len() != neighbors.len() {
+		return Err(runtime!(
+			"Incorrect list length for conduct_with_many ({} neighbors, but {} coefficients).",
+			neighbors.len(),
+			coefficients.len()
+		));
+	}
+	let mix_id = mix_index(src)?;
+	let mut neighbor_coefficients: BTreeMap<usize, (Value, f32)> = BTreeMap::new();
+	for i in 1..=neighbors.len() {
+		let neighbor = neighbors.get(i)?;
+		let idx = mix_index(&neighbor)
+			.map_err(|_| runtime!("Entry {} of the neighbor list isn't a gas mixture", i))?;
+		let coefficient = coefficients
+			.get(i)?
+			.as_number()
+			.map_err(|_| runtime!("Entry {} of the coefficient list isn't a number", i))?;
+		// keyed by ID so that each neighbor gets locked only once, and never src
+		if idx != mix_id {
+			neighbor_coefficients.insert(idx, (neighbor, coefficient));
+		}
+	}
+	GasArena::with_all_mixtures(|all_mixtures| {
+		let mut mix = all_mixtures
+			.get(mix_id)
+			.ok_or_else(|| runtime!("No gas mixture with ID {} exists!", mix_id))?
+			.write();
+		let mut neighbor_locks = neighbor_coefficients
+			.iter()
+			.map(|(&id, (neighbor, coefficient))| {
+				all_mixtures
+					.get(id)
+					.map(|lock| (neighbor, lock.write(), *coefficient))
+					.ok_or_else(|| runtime!("No gas mixture with ID {} exists!", id))
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		let mut neighbor_mixes: Vec<(&mut Mixture, f32)> = neighbor_locks
+			.iter_mut()
+			.map(|(_, neighbor_mix, coefficient)| (&mut **neighbor_mix, *coefficient))
+			.collect();
+		let fluxes = mix.conduct_with_many(&mut neighbor_mixes);
+		let flux_list: List = List::new();
+		for ((neighbor, _, _), flux) in neighbor_locks.iter().zip(fluxes) {
+			flux_list.set(*neighbor, flux)?;
+		}
+		Ok(Value::from(flux_list))
+	})
+}
+
+/// Args: (mixture, coefficient). Moves each gas `coefficient` of the way towards equal concentration between the two mixes, carrying heat with it.
+/// A coefficient of 1 fully equalizes; it's clamped between 0 and 1.
+#[hook("/datum/gas_mixture/proc/gas_share")]
+fn _gas_share_hook(other: Value, coefficient: Value) {
+	let coefficient = coefficient.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mixes_mut(src, other, |our_mix, other_mix| {
+		our_mix.gas_share(other_mix, coefficient);
+		Ok(Value::null())
+	})
+}
+
 /// Returns: a list of the gases in the mixture, associated with their IDs.
 #[hook("/datum/gas_mixture/proc/get_gases")]
 fn _get_gases_hook() {
@@ -170,6 +620,85 @@ fn _get_gases_hook() {
 	})
 }
 
+/// Returns: as get_gases, but sorted from the gas the mixture has the most of to the least. Equal amounts keep the order the gases were registered in.
+#[hook("/datum/gas_mixture/proc/get_gases_sorted")]
+fn _get_gases_sorted_hook() {
+	with_mix(src, |mix| {
+		let gases_list: List = List::new();
+		for (idx, _) in mix.gases_by_amount() {
+			gases_list.append(gas_idx_to_id(idx)?);
+		}
+		Ok(Value::from(gases_list))
+	})
+}
+
+/// Returns: the ID of the gas the mixture has the most of, or null if it's empty. Ties go to whichever gas was registered first.
+#[hook("/datum/gas_mixture/proc/get_dominant_gas")]
+fn _get_dominant_gas_hook() {
+	with_mix(src, |mix| match mix.dominant_gas() {
+		Some(idx) => gas_idx_to_id(idx),
+		None => Ok(Value::null()),
+	})
+}
+
+/// Returns: the number of different gases in the mixture, i.e. the length of the list `get_gases` would return.
+#[hook("/datum/gas_mixture/proc/get_gas_count")]
+fn _get_gas_count_hook() {
+	with_mix(src, |mix| Ok(Value::from(mix.gas_count() as f32)))
+}
+
+/// Checks that the mixture only has gases the current gas table knows about, e.g. after the gas list changed under stored mixtures.
+/// Returns: true if every gas index in the mixture is valid, false otherwise.
+#[hook("/datum/gas_mixture/proc/has_valid_indices")]
+fn _has_valid_indices_hook() {
+	with_mix(src, |mix| {
+		Ok(Value::from(mix.validate_indices_against_gas_table()))
+	})
+}
+
+/// Args: (threshold). As get_gases, but only returns gases with more than `threshold` moles, e.g. to show trace gases get_gases would hide.
+/// Returns: a list of the gases in the mixture above the threshold, associated with their IDs.
+#[hook("/datum/gas_mixture/proc/get_gases_above")]
+fn _get_gases_above_hook(threshold: Value) {
+	let threshold = threshold.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mix(src, |mix| {
+		let gases_list: List = List::new();
+		for (idx, _) in mix.gases_above(threshold) {
+			gases_list.append(gas_idx_to_id(idx)?);
+		}
+		Ok(Value::from(gases_list))
+	})
+}
+
+/// Returns: an associative list of the gases in the mixture to how much of its heat capacity each one makes up.
+/// If the mixture's minimum heat capacity is higher than what its gases add up to, the difference is included under "MIN".
+#[hook("/datum/gas_mixture/proc/heat_capacity_breakdown")]
+fn _heat_capacity_breakdown_hook() {
+	with_mix(src, |mix| {
+		let breakdown_list: List = List::new();
+		let mut gas_heat_capacity = 0.0;
+		for (idx, amt) in mix.enumerate() {
+			if amt > GAS_MIN_MOLES {
+				let partial = mix.partial_heat_capacity(idx);
+				gas_heat_capacity += partial;
+				breakdown_list.set(gas_idx_to_id(idx)?, partial)?;
+			}
+		}
+		let floor = mix.get_min_heat_capacity() - gas_heat_capacity;
+		if floor > 0.0 {
+			breakdown_list.set(byond_string!("MIN"), floor)?;
+		}
+		Ok(Value::from(breakdown_list))
+	})
+}
+
 /// Args: (temperature). Sets the temperature of the mixture. Will be set to 2.7 if it's too low.
 #[hook("/datum/gas_mixture/proc/set_temperature")]
 fn _set_temperature_hook(arg_temp: Value) {
@@ -220,6 +749,24 @@ fn _set_volume_hook(vol_arg: Value) {
 	})
 }
 
+/// Args: (volume). Sets the volume, scaling every gas so the pressure stays the same, unlike set_volume. Doesn't change the temperature.
+/// Runtimes if the volume isn't positive.
+#[hook("/datum/gas_mixture/proc/scale_volume")]
+fn _scale_volume_hook(vol_arg: Value) {
+	let volume = vol_arg.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mix_mut(src, |mix| {
+		mix.scale_volume(volume)?;
+		Ok(Value::null())
+	})
+}
+
 /// Args: (gas_id). Returns: the amount of substance of the given gas, in moles.
 #[hook("/datum/gas_mixture/proc/get_moles")]
 fn _get_moles_hook(gas_id: Value) {
@@ -228,18 +775,132 @@ fn _get_moles_hook(gas_id: Value) {
 	})
 }
 
+/// Args: (gas_id). Returns: true if the mixture has more than a trace of the given gas, false otherwise.
+#[hook("/datum/gas_mixture/proc/has_gas")]
+fn _has_gas_hook(gas_id: Value) {
+	with_mix(src, |mix| {
+		Ok(Value::from(mix.gas_present(gas_idx_from_value(gas_id)?)))
+	})
+}
+
+/// Args: (gas_id). Returns: the partial pressure of the given gas, in kilopascals; 0 if the mixture has none of it.
+#[hook("/datum/gas_mixture/proc/partial_pressure")]
+fn _partial_pressure_hook(gas_id: Value) {
+	with_mix(src, |mix| {
+		Ok(Value::from(
+			mix.partial_pressure(gas_idx_from_value(gas_id)?),
+		))
+	})
+}
+
+/// Returns: an associative list of each gas in the mixture to its partial pressure, in kilopascals, for analyzers and the like.
+#[hook("/datum/gas_mixture/proc/partial_pressures")]
+fn _partial_pressures_hook() {
+	with_mix(src, |mix| {
+		let pressures_list: List = List::new();
+		for (idx, pressure) in mix.partial_pressures() {
+			pressures_list.set(gas_idx_to_id(idx)?, pressure)?;
+		}
+		Ok(Value::from(pressures_list))
+	})
+}
+
+/// Args: (gas_id). Returns: the fraction of the mixture's moles that are the given gas, from 0 to 1; 0 if the mixture is empty.
+#[hook("/datum/gas_mixture/proc/get_fraction")]
+fn _get_fraction_hook(gas_id: Value) {
+	with_mix(src, |mix| {
+		Ok(Value::from(mix.mole_fraction(gas_idx_from_value(gas_id)?)))
+	})
+}
+
+/// Returns: an associative list of each gas in the mixture to the fraction of the mixture's moles it makes up.
+#[hook("/datum/gas_mixture/proc/get_fractions")]
+fn _get_fractions_hook() {
+	with_mix(src, |mix| {
+		let fractions_list: List = List::new();
+		for (idx, amt) in mix.enumerate() {
+			if amt > GAS_MIN_MOLES {
+				fractions_list.set(gas_idx_to_id(idx)?, mix.mole_fraction(idx))?;
+			}
+		}
+		Ok(Value::from(fractions_list))
+	})
+}
+
+/// Args: (gas_name). Returns: the moles of the gas with the given ID string, e.g. "o2". Runtimes with the list of valid IDs if it isn't one.
+#[hook("/datum/gas_mixture/proc/get_moles_by_name")]
+fn _get_moles_by_name_hook(gas_name: Value) {
+	let name = gas_name.as_string()?;
+	let idx = gas_idx_from_string(&name).map_err(|_| {
+		let valid_ids = with_gas_info(|gases| {
+			gases
+				.iter()
+				.map(|gas| gas.id.to_string())
+				.collect::<Vec<_>>()
+		});
+		runtime!(
+			"Invalid gas ID: {}. Valid IDs are: {}",
+			name,
+			valid_ids.join(", ")
+		)
+	})?;
+	with_mix(src, |mix| Ok(Value::from(mix.get_moles(idx))))
+}
+
+/// Args: (gases). Returns: an associative list of each given gas to its moles in the mix, with anything at or below `GAS_MIN_MOLES` as 0.
+/// Runtimes naming the first entry that isn't a valid gas.
+#[hook("/datum/gas_mixture/proc/get_moles_multi")]
+fn _get_moles_multi_hook(gas_list: Value) {
+	let gases = gas_list.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let entries = (1..=gases.len())
+		.map(|i| {
+			let gas_id = gases.get(i)?;
+			let idx = gas_idx_from_value(&gas_id)
+				.map_err(|_| runtime!("Unknown gas: {}", gas_id.as_string().unwrap_or_default()))?;
+			Ok((gas_id, idx))
+		})
+		.collect::<Result<Vec<_>, auxtools::Runtime>>()?;
+	with_mix(src, |mix| {
+		let moles_list: List = List::new();
+		for (gas_id, idx) in &entries {
+			let amt = mix.get_moles(*idx);
+			moles_list.set(gas_id, if amt > GAS_MIN_MOLES { amt } else { 0.0 })?;
+		}
+		Ok(Value::from(moles_list))
+	})
+}
+
+/// Args: (gas_a, gas_b, error_on_zero). If error_on_zero is true, runtimes if there's none of gas_b in the mix.
+/// Returns: the ratio of the moles of gas_a to the moles of gas_b; infinity if there's none of gas_b, or NaN if there's none of either.
+#[hook("/datum/gas_mixture/proc/mole_ratio")]
+fn _mole_ratio_hook(gas_a: Value, gas_b: Value, error_on_zero: Value) {
+	let a = gas_idx_from_value(gas_a)?;
+	let b = gas_idx_from_value(gas_b)?;
+	let error_on_zero = error_on_zero.as_bool().unwrap_or(false);
+	with_mix(src, |mix| {
+		if error_on_zero && mix.get_moles(b) <= 0.0 {
+			return Err(runtime!(
+				"Attempted to get the mole ratio to {}, which isn't in the mix.",
+				gas_b.as_string().unwrap_or_default()
+			));
+		}
+		Ok(Value::from(mix.mole_ratio(a, b)))
+	})
+}
+
 /// Args: (gas_id, moles). Sets the amount of substance of the given gas, in moles.
 #[hook("/datum/gas_mixture/proc/set_moles")]
 fn _set_moles_hook(gas_id: Value, amt_val: Value) {
 	let vf = amt_val.as_number()?;
-	if !vf.is_finite() {
-		return Err(runtime!("Attempted to set moles to NaN or infinity."));
-	}
-	if vf < 0.0 {
-		return Err(runtime!("Attempted to set moles to a negative number."));
-	}
 	with_mix_mut(src, |mix| {
-		mix.set_moles(gas_idx_from_value(gas_id)?, vf);
+		mix.try_set_moles(gas_idx_from_value(gas_id)?, vf)?;
 		Ok(Value::null())
 	})
 }
@@ -302,6 +963,36 @@ fn _adjust_multi_hook() {
 	}
 }
 
+/// Args: (amounts). Amounts is an associative list of gas IDs to moles, e.g. list("o2" = 20, "n2" = 80). Sets every one of them at once.
+/// If any amount is negative, NaN or infinite, nothing is set and this runtimes.
+#[hook("/datum/gas_mixture/proc/set_moles_multi")]
+fn _set_moles_multi_hook(amounts_list: Value) {
+	let amounts = gas_amounts_from_list(amounts_list)?;
+	with_mix_mut(src, |mix| {
+		mix.try_set_multi(&amounts)?;
+		Ok(Value::null())
+	})
+}
+
+/// Args: (deltas, heat). Deltas is an associative list of gas IDs to how many moles to add, or remove if negative, e.g. list("plasma" = -10, "co2" = 10).
+/// Applies all of them, then adds heat, in joules, using the heat capacity after the changes; the whole effect of a reaction at once.
+#[hook("/datum/gas_mixture/proc/apply_reaction_effect")]
+fn _apply_reaction_effect_hook(deltas_list: Value, heat: Value) {
+	let deltas = gas_amounts_from_list(deltas_list)?;
+	let heat = heat.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mix_mut(src, |mix| {
+		mix.apply_reaction_effect(&deltas, heat);
+		Ok(Value::null())
+	})
+}
+
 ///Args: (amount). Adds the given amount to each gas.
 #[hook("/datum/gas_mixture/proc/add")]
 fn _add_hook(num_val: Value) {
@@ -322,6 +1013,15 @@ fn _subtract_hook(num_val: Value) {
 	})
 }
 
+/// Args: (mixture). Takes the amount of each gas in the argument mixture away from src, stopping at zero, without changing src's temperature.
+#[hook("/datum/gas_mixture/proc/subtract_mixture")]
+fn _subtract_mixture_hook(other: Value) {
+	with_mixes_mut(src, other, |src_mix, other_mix| {
+		src_mix.subtract(other_mix);
+		Ok(Value::null())
+	})
+}
+
 ///Args: (coefficient). Multiplies all gases by this amount.
 #[hook("/datum/gas_mixture/proc/multiply")]
 fn _multiply_hook(num_val: Value) {
@@ -386,6 +1086,17 @@ fn get_by_flag_hook(flag_val: Value) {
 	})
 }
 
+/// Args: (mixture, ratios). Ratios is an associative list of gas IDs to the ratio of that gas to move from `src` into `mixture`, e.g. list("o2" = 0.5, "plasma" = 0.1).
+/// Gases not in the list aren't moved at all.
+#[hook("/datum/gas_mixture/proc/remove_ratios_into")]
+fn _remove_ratios_into_hook(into: Value, ratios_list: Value) {
+	let ratios = gas_amounts_from_list(ratios_list)?;
+	with_mixes_mut(src, into, |src_gas, dest_gas| {
+		src_gas.remove_ratios_into(&ratios, dest_gas);
+		Ok(Value::null())
+	})
+}
+
 /// Args: (mixture, ratio, gas_list). Takes gases given by `gas_list` and moves `ratio` amount of those gases from `src` into `mixture`.
 #[hook("/datum/gas_mixture/proc/scrub_into")]
 fn _scrub_into_hook(into: Value, ratio_v: Value, gas_list: Value) {
@@ -417,6 +1128,111 @@ fn _scrub_into_hook(into: Value, ratio_v: Value, gas_list: Value) {
 	})
 }
 
+/// Returns: the mixture, packed into a base64 string, e.g. to save it across rounds and load it later with deserialize_b64.
+#[hook("/datum/gas_mixture/proc/serialize_b64")]
+fn _serialize_b64_hook() {
+	with_mix(src, |mix| {
+		Value::from_string(base64_encode(&mix.serialize()))
+	})
+}
+
+/// Args: (string). Overwrites the mixture, volume included, with one packed by serialize_b64, even by an older version of auxmos.
+/// Runtimes, leaving the mixture alone, if the string is malformed, from a newer version, or has gases that don't exist, or if the mixture is immutable.
+#[hook("/datum/gas_mixture/proc/deserialize_b64")]
+fn _deserialize_b64_hook(encoded: Value) {
+	let mut loaded = Mixture::deserialize(&base64_decode(&encoded.as_string()?)?)?;
+	with_mix_mut(src, |mix| {
+		if mix.is_immutable() {
+			return Err(runtime!("Attempted to overwrite an immutable gas mixture"));
+		}
+		*mix = std::mem::take(&mut loaded);
+		Ok(Value::null())
+	})
+}
+
+/// Args: (ratio, gas_list). Takes `ratio` of each of the gases in `gas_list` out of src, as scrub_into does.
+/// Requires `/proc/auxtools_new_gas_mixture`, which should return a new `/datum/gas_mixture`.
+/// Returns: a new gas mixture, with src's volume, holding the gases taken out.
+#[hook("/datum/gas_mixture/proc/remove_gases_ratio")]
+fn _remove_gases_ratio_hook(ratio_v: Value, gas_list: Value) {
+	let ratio = ratio_v.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let gases_to_remove = gas_list.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let gas_vec = (1..=gases_to_remove.len())
+		.map(|i| {
+			let gas_id = gases_to_remove.get(i)?;
+			gas_idx_from_value(&gas_id)
+				.map_err(|_| runtime!("Unknown gas: {}", gas_id.as_string().unwrap_or_default()))
+		})
+		.collect::<Result<Vec<_>, auxtools::Runtime>>()?;
+	let new_mix = new_gas_mixture()?;
+	with_mixes_mut(src, &new_mix, |src_gas, removed| {
+		*removed = src_gas.remove_gases_ratio(ratio, &gas_vec);
+		Ok(())
+	})?;
+	Ok(new_mix)
+}
+
+/// Args: (mixture, gas_list, moles). Moves up to `moles` moles in total of the gases in `gas_list` from `src` into `mixture`, proportionally to how much of each there is.
+/// Returns: how many moles were actually moved.
+#[hook("/datum/gas_mixture/proc/transfer_gases_amount")]
+fn _transfer_gases_amount_hook(into: Value, gas_list: Value, moles_v: Value) {
+	let amount = moles_v.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let gases_to_transfer = gas_list.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let mut gas_vec = (1..=gases_to_transfer.len())
+		.filter_map(|idx| gas_idx_from_value(&gases_to_transfer.get(idx).unwrap()).ok())
+		.collect::<Vec<_>>();
+	// a gas listed twice would otherwise be counted, and moved, twice
+	gas_vec.sort_unstable();
+	gas_vec.dedup();
+	with_mixes_mut(src, into, |src_gas, dest_gas| {
+		Ok(Value::from(
+			src_gas.transfer_gases_amount(amount, &gas_vec, dest_gas),
+		))
+	})
+}
+
+/// Args: (thresholds). An associative list of gas IDs to the temperature each condenses at, e.g. list("water_vapor" = 373.15).
+/// Removes every gas in the list that the mixture is colder than, taking its thermal energy with it.
+/// Returns: an associative list of the gases removed to how many moles of each were removed.
+#[hook("/datum/gas_mixture/proc/condense_below")]
+fn _condense_below_hook(thresholds_list: Value) {
+	let thresholds = gas_amounts_from_list(thresholds_list)?;
+	let removed = with_mix_mut(src, |mix| Ok(mix.condense_below(&thresholds)))?;
+	let removed_list: List = List::new();
+	for (idx, amt) in removed {
+		removed_list.set(gas_idx_to_id(idx)?, amt)?;
+	}
+	Ok(Value::from(removed_list))
+}
+
 /// Marks the mix as immutable, meaning it will never change. This cannot be undone.
 #[hook("/datum/gas_mixture/proc/mark_immutable")]
 fn _mark_immutable_hook() {
@@ -435,35 +1251,276 @@ fn _clear_hook() {
 	})
 }
 
+/// Removes all gases from the mix while keeping its temperature and volume. Does nothing to immutable mixes.
+#[hook("/datum/gas_mixture/proc/clear_keep_temperature")]
+fn _clear_keep_temperature_hook() {
+	with_mix_mut(src, |mix| {
+		mix.clear_keep_temperature();
+		Ok(Value::null())
+	})
+}
+
+/// Args: (mixture, threshold). Threshold is optional, defaulting to the minimum moles delta to move.
+/// Returns: an associative list of gas IDs to the absolute mole difference between the two mixtures, for every gas differing by more than the threshold.
+/// The temperature difference is always included, under "TEMP".
+#[hook("/datum/gas_mixture/proc/get_gas_differences")]
+fn _get_gas_differences_hook(other: Value) {
+	let threshold = args
+		.get(1)
+		.and_then(|v| v.as_number().ok())
+		.unwrap_or_else(minimum_moles_delta);
+	with_mixes(src, other, |gas_one, gas_two| {
+		let diff_list: List = List::new();
+		for (idx, delta) in gas_one.gas_differences(gas_two, threshold) {
+			diff_list.set(gas_idx_to_id(idx)?, delta)?;
+		}
+		diff_list.set(
+			byond_string!("TEMP"),
+			(gas_one.get_temperature() - gas_two.get_temperature()).abs(),
+		)?;
+		Ok(Value::from(diff_list))
+	})
+}
+
+/// Args: (mixture). Returns: a list of the ID of the gas whose moles differ most between the two mixtures and by how much, e.g. list("o2", 5).
+/// If no gas differs at all, the ID is null and the difference 0.
+#[hook("/datum/gas_mixture/proc/compare_detailed")]
+fn _compare_detailed_hook(other: Value) {
+	with_mixes(src, other, |gas_one, gas_two| {
+		let (idx, delta) = gas_one.compare_detailed(gas_two);
+		let result: List = List::new();
+		if delta > 0.0 {
+			result.append(gas_idx_to_id(idx)?);
+		} else {
+			result.append(Value::null());
+		}
+		result.append(delta);
+		Ok(Value::from(result))
+	})
+}
+
 /// Returns: true if the two mixtures are different enough for processing, false otherwise.
 #[hook("/datum/gas_mixture/proc/compare")]
 fn _compare_hook(other: Value) {
+	let min_moles_delta = minimum_moles_delta();
 	with_mixes(src, other, |gas_one, gas_two| {
 		Ok(Value::from(
-			gas_one.temperature_compare(gas_two)
-				|| gas_one.compare_with(gas_two, MINIMUM_MOLES_DELTA_TO_MOVE),
+			gas_one.temperature_compare(gas_two) || gas_one.compare_with(gas_two, min_moles_delta),
 		))
 	})
 }
 
+/// Returns: a hash of the mix's composition, as a hex string, the same for any two mixes with the same mole fractions (to within 5%) whatever their size or temperature.
+#[hook("/datum/gas_mixture/proc/spectral_signature")]
+fn _spectral_signature_hook() {
+	with_mix(src, |mix| {
+		Value::from_string(format!("{:016x}", mix.spectral_signature()))
+	})
+}
+
+/// Args: (mixture). Returns: how similar the two mixes' compositions are, from 0 to 1, where 1 means the same spectral_signature.
+#[hook("/datum/gas_mixture/proc/signature_similarity")]
+fn _signature_similarity_hook(other: Value) {
+	with_mixes(src, other, |gas_one, gas_two| {
+		Ok(Value::from(gas_one.signature_similarity(gas_two)))
+	})
+}
+
+/// Args: (mixture, moles_tolerance, temperature_tolerance). Tolerances default to GAS_MIN_MOLES and MINIMUM_TEMPERATURE_DELTA_TO_CONSIDER if not given.
+/// Returns: true if every gas is within moles_tolerance of the other mixture's, and the temperatures are within temperature_tolerance, false otherwise.
+#[hook("/datum/gas_mixture/proc/equals")]
+fn _equals_hook(other: Value, moles_tolerance: Value, temperature_tolerance: Value) {
+	mixes_approx_equal(src, other, moles_tolerance, temperature_tolerance)
+}
+
+/// Args: (mixture, mole_eps, temp_eps). The same as equals.
+#[hook("/datum/gas_mixture/proc/approx_equal")]
+fn _approx_equal_hook(other: Value, mole_eps: Value, temp_eps: Value) {
+	mixes_approx_equal(src, other, mole_eps, temp_eps)
+}
+
+/// Shared by `equals` and `approx_equal`.
+fn mixes_approx_equal(
+	src: &Value,
+	other: &Value,
+	moles_tolerance: &Value,
+	temperature_tolerance: &Value,
+) -> DMResult {
+	let moles_tolerance = moles_tolerance.as_number().unwrap_or(GAS_MIN_MOLES);
+	let temperature_tolerance = temperature_tolerance
+		.as_number()
+		.unwrap_or(constants::MINIMUM_TEMPERATURE_DELTA_TO_CONSIDER);
+	with_mixes(src, other, |gas_one, gas_two| {
+		Ok(Value::from(gas_one.approx_eq(
+			gas_two,
+			moles_tolerance,
+			temperature_tolerance,
+		)))
+	})
+}
+
+/// Args: (neighbor_list). A list of the gas mixtures this one would share with.
+/// Returns: true if the mixture can react, has a visible gas, or is different enough from any of the neighbors for processing, false otherwise.
+#[hook("/datum/gas_mixture/proc/needs_processing")]
+fn _needs_processing_hook(neighbor_list: Value) {
+	let neighbors = neighbor_list.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let mix_id = mix_index(src)?;
+	let neighbor_ids: Vec<usize> = (1..=neighbors.len())
+		.filter_map(|i| mix_index(&neighbors.get(i).ok()?).ok())
+		.filter(|&id| id != mix_id)
+		.collect();
+	GasArena::with_all_mixtures(|all_mixtures| {
+		let mix = all_mixtures
+			.get(mix_id)
+			.ok_or_else(|| runtime!("No gas mixture with ID {} exists!", mix_id))?
+			.read();
+		let neighbor_locks: Vec<_> = neighbor_ids
+			.iter()
+			.filter_map(|&id| Some(all_mixtures.get(id)?.read()))
+			.collect();
+		let neighbor_mixes: Vec<&Mixture> = neighbor_locks.iter().map(|lock| &**lock).collect();
+		Ok(Value::from(mix.needs_processing(&neighbor_mixes)))
+	})
+}
+
+/// Args: (mixture_list). A list of other gas mixtures to average with.
+/// Works out what src would be if it were joined up with all of the mixtures, evened out, then split off again at its own volume, without changing any of them.
+/// Requires `/proc/auxtools_new_gas_mixture`, which should return a new `/datum/gas_mixture`.
+/// Returns: a new gas mixture with the average, at src's volume.
+#[hook("/datum/gas_mixture/proc/average_with")]
+fn _average_with_hook(mixture_list: Value) {
+	let others = mixture_list.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let mix_id = mix_index(src)?;
+	let other_ids: std::collections::BTreeSet<usize> = (1..=others.len())
+		.filter_map(|i| mix_index(&others.get(i).ok()?).ok())
+		.filter(|&id| id != mix_id)
+		.collect();
+	let mut average = GasArena::with_all_mixtures(|all_mixtures| {
+		let mix = all_mixtures
+			.get(mix_id)
+			.ok_or_else(|| runtime!("No gas mixture with ID {} exists!", mix_id))?
+			.read();
+		let other_locks: Vec<_> = other_ids
+			.iter()
+			.filter_map(|&id| Some(all_mixtures.get(id)?.read()))
+			.collect();
+		let other_mixes: Vec<&Mixture> = other_locks.iter().map(|lock| &**lock).collect();
+		Ok(mix.average_with(&other_mixes))
+	})?;
+	let new_mix = new_gas_mixture()?;
+	with_mix_mut(&new_mix, |mix| {
+		*mix = std::mem::take(&mut average);
+		Ok(())
+	})?;
+	Ok(new_mix)
+}
+
 /// Args: (holder). Runs all reactions on this gas mixture. Holder is used by the reactions, and can be any arbitrary datum or null.
+/// If called on a mixture that is already reacting, e.g. by a reaction on that same mixture, does nothing and returns REACTION_BUSY.
 #[hook("/datum/gas_mixture/proc/react")]
 fn _react_hook(holder: Value) {
-	let mut ret = ReactionReturn::NO_REACTION;
-	let reactions = with_mix(src, |mix| Ok(mix.all_reactable()))?;
-	for reaction in reactions {
-		ret |= ReactionReturn::from_bits_truncate(
-			react_by_id(reaction, src, holder)?
-				.as_number()
-				.unwrap_or_default() as u32,
-		);
-		if ret.contains(ReactionReturn::STOP_REACTIONS) {
-			return Ok(Value::from(ret.bits() as f32));
+	react_mixture(src, holder, |_| {})
+}
+
+/// As react, but with a null holder, for tests and anything else with nothing for the reactions to affect.
+/// Reactions get null for the holder just as with react(null), so ones with side effects on it should already handle that.
+#[hook("/datum/gas_mixture/proc/react_pure")]
+fn _react_pure_hook() {
+	react_mixture(src, &Value::null(), |_| {})
+}
+
+/// Runs all reactions on the given gas mixture with the given holder; see the react hook.
+/// `on_reacted` is called with the ID of each reaction that returned REACTING.
+fn react_mixture(
+	src: &Value,
+	holder: &Value,
+	mut on_reacted: impl FnMut(ReactionIdentifier),
+) -> DMResult {
+	let ret = react_guarded(mix_index(src)?, || {
+		let mut ret = ReactionReturn::NO_REACTION;
+		for reaction in with_mix(src, |mix| mix.all_reactable())? {
+			let result = ReactionReturn::from_bits_truncate(
+				react_by_id(reaction, src, holder)?
+					.as_number()
+					.unwrap_or_default() as u32,
+			);
+			if result.contains(ReactionReturn::REACTING) {
+				on_reacted(reaction);
+			}
+			ret |= result;
+			if ret.contains(ReactionReturn::STOP_REACTIONS) {
+				break;
+			}
 		}
-	}
+		Ok(ret)
+	})?;
 	Ok(Value::from(ret.bits() as f32))
 }
 
+/// Args: (holder). As `react`, but returns a list of the string IDs of the reactions that actually reacted, in the order they did.
+/// Slower than `react`, so only use it for analytics.
+#[hook("/datum/gas_mixture/proc/react_tracked")]
+fn _react_tracked_hook(holder: Value) {
+	let mut reacted = Vec::new();
+	react_mixture(src, holder, |reaction| reacted.push(reaction))?;
+	let reacted_list: List = List::new();
+	for name in reacted.into_iter().filter_map(reaction_name) {
+		reacted_list.append(Value::from_string(name)?);
+	}
+	Ok(Value::from(reacted_list))
+}
+
+/// Returns: how vigorously the mixture is reacting, from 0 to 1, based on how far past each possible reaction's requirements it is.
+#[hook("/datum/gas_mixture/proc/reaction_intensity")]
+fn _reaction_intensity_hook() {
+	with_mix(src, |mix| Ok(Value::from(mix.reaction_intensity())))
+}
+
+/// Runs all reactions on a copy of this gas mixture, leaving this one untouched, to see what react would do.
+/// The reactions get a null holder, as with react_pure, so they have no turf or other holder to affect; any holder passed in is ignored.
+/// Reactions that need a holder to react at all won't show up here.
+/// Requires `/proc/auxtools_new_gas_mixture`, which should return a new `/datum/gas_mixture`.
+/// Returns: an associative list of gas IDs to how much each would change by, plus "TEMP" for the temperature change and "FLAGS" for what react would return.
+#[hook("/datum/gas_mixture/proc/react_dry_run")]
+fn _react_dry_run_hook() {
+	let before = with_mix(src, |mix| Ok(mix.clone()))?;
+	let copy = new_gas_mixture()?;
+	with_mix_mut(&copy, |mix| {
+		*mix = before.clone();
+		Ok(())
+	})?;
+	let flags = copy.call("react", &[&Value::null()])?;
+	with_mix(&copy, |after| {
+		let diff_list: List = List::new();
+		for idx in 0..total_num_gases() {
+			let delta = after.get_moles(idx) - before.get_moles(idx);
+			if delta != 0.0 {
+				diff_list.set(gas_idx_to_id(idx)?, delta)?;
+			}
+		}
+		diff_list.set(
+			byond_string!("TEMP"),
+			after.get_temperature() - before.get_temperature(),
+		)?;
+		diff_list.set(byond_string!("FLAGS"), &flags)?;
+		Ok(Value::from(diff_list))
+	})
+}
+
 /// Args: (heat). Adds a given amount of heat to the mixture, i.e. in joules taking into account capacity.
 #[hook("/datum/gas_mixture/proc/adjust_heat")]
 fn _adjust_heat_hook() {
@@ -485,19 +1542,69 @@ fn _adjust_heat_hook() {
 	})
 }
 
+/// Args: (heat, max_temperature). As adjust_heat, but won't heat the mixture past max_temperature.
+/// Returns: the heat actually added, in joules.
+#[hook("/datum/gas_mixture/proc/adjust_heat_capped")]
+fn _adjust_heat_capped_hook(heat: Value, max_temperature: Value) {
+	let heat = heat.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let max_temperature = max_temperature.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mix_mut(src, |mix| {
+		Ok(Value::from(mix.adjust_heat_capped(heat, max_temperature)))
+	})
+}
+
+/// Args: (min, max). Clamps the temperature of the mixture between min and max, e.g. for a heater's safety limits. min is never lower than TCMB.
+/// Returns: the temperature afterwards.
+#[hook("/datum/gas_mixture/proc/clamp_temperature")]
+fn _clamp_temperature_hook(min: Value, max: Value) {
+	let min = min.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let max = max.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mix_mut(src, |mix| Ok(Value::from(mix.clamp_temperature(min, max))))
+}
+
 /// Args: (mixture, amount). Takes the `amount` given and transfers it from `src` to `mixture`.
+/// Returns: the moles actually transferred.
 #[hook("/datum/gas_mixture/proc/transfer_to")]
 fn _transfer_hook(other: Value, moles: Value) {
 	with_mixes_mut(src, other, |our_mix, other_mix| {
-		other_mix.merge(&our_mix.remove(moles.as_number().map_err(|_| {
+		let removed = our_mix.remove(moles.as_number().map_err(|_| {
 			runtime!(
 				"Attempt to interpret non-number value as number {} {}:{}",
 				std::file!(),
 				std::line!(),
 				std::column!()
 			)
-		})?));
-		Ok(Value::null())
+		})?);
+		other_mix.merge(&removed);
+		Ok(Value::from(removed.total_moles()))
 	})
 }
 
@@ -530,6 +1637,27 @@ fn _equalize_with_hook(total: Value) {
 	})
 }
 
+/// Returns: a list of two associative lists, the first of each oxidizer in the mixture to its oxidation power and the second of each fuel to its fuel amount,
+/// i.e. what each gas adds to the totals from get_oxidation_power and get_fuel_amount. Gases too cold to burn aren't included.
+#[hook("/datum/gas_mixture/proc/get_burnability_breakdown")]
+fn _get_burnability_breakdown_hook() {
+	with_mix(src, |mix| {
+		let (fuels, oxidizers) = with_gas_info(|gas_info| mix.get_fire_info_with_lock(gas_info));
+		let oxidizer_list: List = List::new();
+		for (idx, _, power) in oxidizers {
+			oxidizer_list.set(gas_idx_to_id(idx)?, power)?;
+		}
+		let fuel_list: List = List::new();
+		for (idx, _, amount) in fuels {
+			fuel_list.set(gas_idx_to_id(idx)?, amount)?;
+		}
+		let breakdown_list: List = List::new();
+		breakdown_list.append(Value::from(oxidizer_list));
+		breakdown_list.append(Value::from(fuel_list));
+		Ok(Value::from(breakdown_list))
+	})
+}
+
 /// Args: (temperature). Returns: how much fuel for fire is in the mixture at the given temperature. If temperature is omitted, just uses current temperature instead.
 #[hook("/datum/gas_mixture/proc/get_fuel_amount")]
 fn _fuel_amount_hook(temp: Value) {
@@ -567,6 +1695,7 @@ fn _share_ratio_hook(other_gas: Value, ratio_val: Value, one_way_val: Value) {
 	let one_way = one_way_val.as_bool().unwrap_or(false);
 	let ratio = ratio_val.as_number().ok().map_or(0.6);
 	let mut inbetween = Mixture::new();
+	let min_moles_delta = minimum_moles_delta();
 	if one_way {
 		with_mixes_custom(src, other_gas, |src_lock, other_lock| {
 			let src_mix = src_lock.write();
@@ -578,7 +1707,7 @@ fn _share_ratio_hook(other_gas: Value, ratio_val: Value, one_way_val: Value) {
 			src_mix.merge(inbetween);
 			Ok(Value::from(
 				src_mix.temperature_compare(other_mix)
-					|| src_mix.compare_with(other_mix, MINIMUM_MOLES_DELTA_TO_MOVE),
+					|| src_mix.compare_with(other_mix, min_moles_delta),
 			))
 		})
 	} else {
@@ -590,7 +1719,7 @@ fn _share_ratio_hook(other_gas: Value, ratio_val: Value, one_way_val: Value) {
 			other_mix.merge(inbetween);
 			Ok(Value::from(
 				src_mix.temperature_compare(other_mix)
-					|| src_mix.compare_with(other_mix, MINIMUM_MOLES_DELTA_TO_MOVE),
+					|| src_mix.compare_with(other_mix, min_moles_delta),
 			))
 		})
 	}
@@ -613,17 +1742,10 @@ fn _equalize_all_hook() {
 			)
 		})?;
 	let gas_list: BTreeSet<usize> = (1..=value_list.len())
-		.filter_map(|i| {
-			value_list
-				.get(i)
-				.unwrap_or_else(|_| Value::null())
-				.get_number(byond_string!("_extools_pointer_gasmixture"))
-				.ok()
-				.map(|f| f.to_bits() as usize)
-		})
+		.filter_map(|i| mix_index(&value_list.get(i).unwrap_or_else(|_| Value::null())).ok())
 		.collect(); // collect because get_number is way slower than the one-time allocation
 	GasArena::with_all_mixtures(move |all_mixtures| {
-		let mut tot = gas::Mixture::new();
+		let mut tot = gas::ScratchMix::new();
 		let mut tot_vol: f64 = 0.0;
 		for &id in &gas_list {
 			if let Some(src_gas_lock) = all_mixtures.get(id) {
@@ -646,6 +1768,336 @@ fn _equalize_all_hook() {
 	Ok(Value::null())
 }
 
+/// Args: (list). Takes an associative list of gas mixtures to weights, e.g. flow rates, and makes them all identical, scaled to their respective volumes.
+/// Each mixture's moles, heat and volume count towards the average in proportion to its weight; with all weights at 1, this is identical to `equalize_all_gases_in_list`.
+/// Runtimes, changing nothing, if any weight isn't a positive number.
+#[hook("/proc/equalize_all_gases_in_list_weighted")]
+fn _equalize_all_weighted_hook() {
+	use std::collections::BTreeMap;
+	let value_list = args
+		.get(0)
+		.ok_or_else(|| runtime!("Wrong number of args for weighted equalize all: 0"))?
+		.as_list()
+		.map_err(|_| {
+			runtime!(
+				"Attempt to interpret non-list value as list {} {}:{}",
+				std::file!(),
+				std::line!(),
+				std::column!()
+			)
+		})?;
+	let mut gas_list: BTreeMap<usize, f32> = BTreeMap::new();
+	for i in 1..=value_list.len() {
+		let mix_val = value_list.get(i)?;
+		let idx = match mix_index(&mix_val) {
+			Ok(idx) => idx,
+			Err(_) => continue,
+		};
+		let weight = value_list.get(mix_val)?.as_number().unwrap_or(f32::NAN);
+		// anything else would give the average negative or NaN moles
+		if !weight.is_finite() || weight <= 0.0 {
+			return Err(runtime!(
+				"Invalid weight for weighted equalize all: {}",
+				weight
+			));
+		}
+		gas_list.insert(idx, weight);
+	}
+	GasArena::with_all_mixtures(move |all_mixtures| {
+		let mut tot = gas::ScratchMix::new();
+		let mut tot_vol: f64 = 0.0;
+		for (&id, &weight) in &gas_list {
+			if let Some(src_gas_lock) = all_mixtures.get(id) {
+				let src_gas = src_gas_lock.read();
+				tot.merge_scaled(&src_gas, weight);
+				tot_vol += f64::from(src_gas.volume) * f64::from(weight);
+			}
+		}
+		if tot_vol > 0.0 {
+			for &id in gas_list.keys() {
+				if let Some(dest_gas_lock) = all_mixtures.get(id) {
+					let dest_gas = &mut dest_gas_lock.write();
+					let vol = dest_gas.volume; // don't wanna borrow it in the below
+					dest_gas.copy_from_mutable(&tot);
+					dest_gas.multiply((f64::from(vol) / tot_vol) as f32);
+				}
+			}
+		}
+	});
+	Ok(Value::null())
+}
+
+/// Args: (list). A list of gas mixtures, e.g. every room on the station.
+/// Returns: the average temperature of the mixtures weighted by heat capacity, i.e. the temperature they'd all settle at if mixed,
+/// so big hot rooms count for more than small cold ones. TCMB if none of them have any heat capacity.
+#[hook("/proc/average_temperature_in_list")]
+fn _average_temperature_in_list_hook(mixture_list: Value) {
+	let mixtures = mixture_list.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let ids: std::collections::BTreeSet<usize> = (1..=mixtures.len())
+		.filter_map(|i| mix_index(&mixtures.get(i).ok()?).ok())
+		.collect();
+	let (energy, capacity) = GasArena::with_all_mixtures(|all_mixtures| {
+		let mut energy: f64 = 0.0;
+		let mut capacity: f64 = 0.0;
+		for &id in &ids {
+			if let Some(lock) = all_mixtures.get(id) {
+				let mix = lock.read();
+				energy += f64::from(mix.thermal_energy());
+				capacity += f64::from(mix.heat_capacity());
+			}
+		}
+		(energy, capacity)
+	});
+	if capacity > 0.0 {
+		Ok(Value::from((energy / capacity) as f32))
+	} else {
+		Ok(Value::from(constants::TCMB))
+	}
+}
+
+/// What `process_mixture_pairs` does to each pair.
+#[derive(Clone, Copy)]
+enum PairOperation {
+	/// `gas_share` with the given coefficient.
+	Share(f32),
+	/// `gas_share` all the way, so both end up with the same concentrations.
+	Equalize,
+	/// `temperature_share` with the given coefficient.
+	Conduct(f32),
+}
+
+/// Args: (pair_list, operation, coefficient). pair_list is a flat list of gas mixtures taken two at a time, e.g. list(a, b, b, c) for the pairs a-b and b-c.
+/// operation is "share", "equalize" or "conduct", as the gas_share and temperature_share procs, or gas_share with a coefficient of 1 for "equalize".
+/// coefficient is optional, defaulting to 1 for "share" and OPEN_HEAT_TRANSFER_COEFFICIENT for "conduct"; "equalize" ignores it.
+/// Everything is done under one lock of the gas arena, rather than one per pair, so it's much faster than calling the procs for every pair.
+/// Returns: the number of pairs processed; pairs of a mixture with itself are skipped.
+#[hook("/proc/process_mixture_pairs")]
+fn _process_mixture_pairs_hook(pair_list: Value, operation: Value, coefficient: Value) {
+	let pair_list = pair_list.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	if pair_list.len() % 2 != 0 {
+		return Err(runtime!(
+			"Incorrect list length for process_mixture_pairs (not divisible by 2)."
+		));
+	}
+	let coefficient = coefficient.as_number().ok();
+	let operation = match operation.as_string()?.as_str() {
+		"share" => PairOperation::Share(coefficient.unwrap_or(1.0)),
+		"equalize" => PairOperation::Equalize,
+		"conduct" => {
+			PairOperation::Conduct(coefficient.unwrap_or(constants::OPEN_HEAT_TRANSFER_COEFFICIENT))
+		}
+		other => return Err(runtime!("Unknown mixture pair operation: {}", other)),
+	};
+	let ids = (1..=pair_list.len())
+		.map(|i| {
+			mix_index(&pair_list.get(i)?)
+				.map_err(|_| runtime!("Entry {} of the pair list isn't a gas mixture", i))
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	GasArena::with_all_mixtures(|all_mixtures| {
+		let mut processed = 0;
+		for pair in ids.chunks_exact(2) {
+			let (a, b) = (pair[0], pair[1]);
+			if a == b {
+				continue;
+			}
+			let (lock_a, lock_b) = match (all_mixtures.get(a), all_mixtures.get(b)) {
+				(Some(lock_a), Some(lock_b)) => (lock_a, lock_b),
+				_ => return Err(runtime!("No gas mixture with ID {} exists!", a.max(b))),
+			};
+			// always lock the lower ID first, so we can't deadlock with anything else doing the same
+			let (mut mix_a, mut mix_b) = if a < b {
+				let mix_a = lock_a.write();
+				(mix_a, lock_b.write())
+			} else {
+				let mix_b = lock_b.write();
+				(lock_a.write(), mix_b)
+			};
+			match operation {
+				PairOperation::Share(coefficient) => mix_a.gas_share(&mut mix_b, coefficient),
+				PairOperation::Equalize => mix_a.gas_share(&mut mix_b, 1.0),
+				PairOperation::Conduct(coefficient) => {
+					mix_a.temperature_share(&mut mix_b, coefficient);
+				}
+			}
+			processed += 1;
+		}
+		Ok(Value::from(processed as f32))
+	})
+}
+
+/// Args: (gas_id). Returns: the specific heat of the gas with that ID.
+#[hook("/proc/get_gas_specific_heat")]
+fn _get_gas_specific_heat_hook(gas_id: Value) {
+	Ok(Value::from(gas_specific_heat(gas_idx_from_value(gas_id)?)?))
+}
+
+/// Reads an associative list of gas IDs to numbers, e.g. list("o2" = 20, "n2" = 80), into gas indices and those numbers.
+fn gas_amounts_from_list(gas_list: &Value) -> Result<Vec<(gas::GasIDX, f32)>, auxtools::Runtime> {
+	let gases = gas_list.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	(1..=gases.len())
+		.map(|i| {
+			let gas_id = gases.get(i)?;
+			let idx = gas_idx_from_value(&gas_id)
+				.map_err(|_| runtime!("Unknown gas: {}", gas_id.as_string().unwrap_or_default()))?;
+			Ok((idx, gases.get(gas_id)?.as_number()?))
+		})
+		.collect()
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+	b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard, padded base64, for passing bytes to and from DM as text.
+fn base64_encode(bytes: &[u8]) -> String {
+	let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+	for chunk in bytes.chunks(3) {
+		let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, &byte)| {
+			bits | (u32::from(byte) << (16 - 8 * i))
+		});
+		for i in 0..4 {
+			if i <= chunk.len() {
+				let sextet = (bits >> (18 - 6 * i)) & 63;
+				encoded.push(char::from(BASE64_ALPHABET[sextet as usize]));
+			} else {
+				encoded.push('=');
+			}
+		}
+	}
+	encoded
+}
+
+/// Reverses `base64_encode`. Padding is optional.
+fn base64_decode(encoded: &str) -> Result<Vec<u8>, auxtools::Runtime> {
+	let encoded = encoded.trim_end_matches('=').as_bytes();
+	if encoded.len() % 4 == 1 {
+		return Err(runtime!("Invalid base64 length: {}", encoded.len()));
+	}
+	let mut bytes = Vec::with_capacity(encoded.len() * 3 / 4);
+	for chunk in encoded.chunks(4) {
+		let bits = chunk.iter().enumerate().try_fold(0u32, |bits, (i, &c)| {
+			let value = BASE64_ALPHABET
+				.iter()
+				.position(|&letter| letter == c)
+				.ok_or_else(|| runtime!("Invalid base64 character: {}", char::from(c)))?;
+			Ok(bits | ((value as u32) << (18 - 6 * i)))
+		})?;
+		bytes.extend_from_slice(&bits.to_be_bytes()[1..chunk.len()]);
+	}
+	Ok(bytes)
+}
+
+/// Builds a mixture from the (list, temperature, volume) args shared by `make_gas_mixture` and `make_immutable_gas_mixture`.
+fn mixture_from_args(
+	gas_list: &Value,
+	temperature: &Value,
+	volume: &Value,
+) -> Result<Mixture, auxtools::Runtime> {
+	let amounts = gas_amounts_from_list(gas_list)?;
+	let temperature = temperature.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let volume = volume.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	Ok(Mixture::from_gas_amounts(&amounts, temperature, volume))
+}
+
+/// Args: (list, temperature, volume). List is an associative list of gas IDs to moles, e.g. list("o2" = 20, "n2" = 80).
+/// Requires `/proc/auxtools_new_gas_mixture`, which should return a new `/datum/gas_mixture`.
+/// Returns: a new gas mixture with those gases, temperature and volume.
+#[hook("/proc/make_gas_mixture")]
+fn _make_gas_mixture_hook(gas_list: Value, temperature: Value, volume: Value) {
+	let mut built = mixture_from_args(gas_list, temperature, volume)?;
+	let new_mix = new_gas_mixture()?;
+	with_mix_mut(&new_mix, |mix| {
+		*mix = std::mem::take(&mut built);
+		Ok(())
+	})?;
+	Ok(new_mix)
+}
+
+/// Args: (list, temperature, volume). As make_gas_mixture, but the new mixture is immutable, e.g. for reference atmospheres.
+/// The mixture is filled in and made immutable under a single lock, so it's never seen half-built.
+/// Requires `/proc/auxtools_new_gas_mixture`, which should return a new `/datum/gas_mixture`.
+/// Returns: a new immutable gas mixture with those gases, temperature and volume.
+#[hook("/proc/make_immutable_gas_mixture")]
+fn _make_immutable_gas_mixture_hook(gas_list: Value, temperature: Value, volume: Value) {
+	let mut built = mixture_from_args(gas_list, temperature, volume)?;
+	built.mark_immutable();
+	let new_mix = new_gas_mixture()?;
+	with_mix_mut(&new_mix, |mix| {
+		*mix = std::mem::take(&mut built);
+		Ok(())
+	})?;
+	Ok(new_mix)
+}
+
+/// Args: (callback_proc). The path of a global proc, as text, e.g. "/proc/cool_mixture", to be called with each gas mixture as its only argument.
+/// The calls are queued as atmos callbacks, in batches, so they happen over the next few `process_atmos_callbacks` rather than right away.
+/// Mixtures deleted before their turn are skipped.
+/// Returns: the number of gas mixtures queued.
+#[hook("/proc/for_each_gas_mixture")]
+fn _for_each_gas_mixture_hook(callback_proc: Value) {
+	const BATCH_SIZE: usize = 1000;
+	let proc_path = callback_proc.as_string()?;
+	if Proc::find(proc_path.as_str()).is_none() {
+		return Err(runtime!("No such proc: {}", proc_path));
+	}
+	let sender = auxcallback::byond_callback_sender();
+	let mut queued = 0;
+	for batch in registered_gas_mixtures().chunks(BATCH_SIZE) {
+		let batch = batch.to_vec();
+		let batch_len = batch.len();
+		let proc_path = proc_path.clone();
+		let sent = sender.try_send(Box::new(move || {
+			let callback = Proc::find(proc_path.as_str())
+				.ok_or_else(|| runtime!("No such proc: {}", proc_path))?;
+			for &id in &batch {
+				if let Some(mix) = registered_gas_mixture(id) {
+					callback.call(&[&mix])?;
+				}
+			}
+			Ok(())
+		}));
+		if sent.is_ok() {
+			queued += batch_len;
+		}
+	}
+	Ok(Value::from(queued as f32))
+}
+
 /// Returns: the amount of gas mixtures that are attached to a byond gas mixture.
 #[hook("/datum/controller/subsystem/air/proc/get_amt_gas_mixes")]
 fn _hook_amt_gas_mixes() {
@@ -658,6 +2110,58 @@ fn _hook_max_gas_mixes() {
 	Ok(Value::from(tot_gases() as f32))
 }
 
+/// Records how many gas mixtures are in use, for get_gas_count_trend. Call it regularly, e.g. once a tick; only the last 60 samples are kept.
+/// Returns: the number of gas mixtures in use.
+#[hook("/proc/sample_gas_count")]
+fn _hook_sample_gas_count() {
+	Ok(Value::from(sample_gas_count() as f32))
+}
+
+/// Returns: how fast the number of gas mixtures in use has been changing over the samples taken by sample_gas_count, in mixtures per sample.
+/// Steadily positive on a quiet server suggests mixtures are leaking.
+#[hook("/proc/get_gas_count_trend")]
+fn _hook_get_gas_count_trend() {
+	Ok(Value::from(gas_count_trend()))
+}
+
+/// Args: (path). Writes the state of every live gas mixture to the given file in one go, for visualizers and analytics.
+/// The file is binary, since BYOND strings can't hold it; see `export_all_gas_state` in gas.rs for the exact layout.
+/// Returns: the number of bytes written.
+#[hook("/proc/export_all_gas_state")]
+fn _hook_export_all_gas_state(path: Value) {
+	let path = path.as_string()?;
+	let blob = export_all_gas_state();
+	std::fs::write(&path, &blob)
+		.map_err(|e| runtime!("Couldn't write gas state to {}: {}", path, e))?;
+	Ok(Value::from(blob.len() as f32))
+}
+
+/// Checks every live gas mixture for corruption, without fixing anything.
+/// Returns: a list of lists of (arena index, reason), one for each corrupt mixture; empty if none are.
+#[hook("/proc/validate_all_gas_mixtures")]
+fn _hook_validate_all_gas_mixtures() {
+	let failures: List = List::new();
+	for (idx, reason) in validate_all_gas_mixtures() {
+		let failure: List = List::new();
+		failure.append(Value::from(idx as f32));
+		failure.append(Value::from_string(reason)?);
+		failures.append(Value::from(failure));
+	}
+	Ok(Value::from(failures))
+}
+
+/// Returns: the total thermal energy of every gas mixture, in joules. Comparing it before and after a tick shows whether energy is being conserved.
+#[hook("/proc/global_thermal_energy")]
+fn _hook_global_thermal_energy() {
+	Ok(Value::from(global_thermal_energy() as f32))
+}
+
+/// Returns: a rough estimate of how many bytes the gas mixture arena is using.
+#[hook("/datum/controller/subsystem/air/proc/get_gas_memory_estimate")]
+fn _hook_gas_memory_estimate() {
+	Ok(Value::from(gas_memory_estimate() as f32))
+}
+
 #[hook("/datum/gas_mixture/proc/__auxtools_parse_gas_string")]
 fn _parse_gas_string(string: Value) {
 	let actual_string = string.as_string()?;
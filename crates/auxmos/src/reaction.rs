@@ -3,7 +3,9 @@ mod hooks;
 
 use auxtools::{byond_string, runtime, shutdown, DMResult, Runtime, Value};
 
-use crate::gas::{gas_idx_to_id, total_num_gases, GasIDX, Mixture};
+use crate::gas::{constants::ReactionReturn, gas_idx_to_id, total_num_gases, GasIDX, Mixture};
+
+use crate::rng::SplitMix64;
 
 use std::cell::RefCell;
 
@@ -13,7 +15,7 @@ pub type ReactionPriority = FloatOrd<f32>;
 
 pub type ReactionIdentifier = u64;
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct Reaction {
 	id: ReactionIdentifier,
 	priority: ReactionPriority,
@@ -22,10 +24,13 @@ pub struct Reaction {
 	min_ener_req: Option<f32>,
 	min_fire_req: Option<f32>,
 	min_gas_reqs: Vec<(GasIDX, f32)>,
+	/// Bitfield of the gases in `min_gas_reqs` that need to be present at all, as in `Mixture::gases_bitfield`.
+	required_gases: u64,
 }
 
-use fxhash::FxBuildHasher;
-use std::collections::HashMap;
+use fxhash::{FxBuildHasher, FxHasher64};
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 
 enum ReactionSide {
 	ByondSide(Value),
@@ -33,7 +38,66 @@ enum ReactionSide {
 }
 
 thread_local! {
-	static REACTION_VALUES: RefCell<HashMap<ReactionIdentifier, ReactionSide, FxBuildHasher>> = Default::default();
+	static REACTION_VALUES: RefCell<HashMap<ReactionIdentifier, (Box<str>, ReactionSide), FxBuildHasher>> = Default::default();
+	static REACTING_MIXTURES: RefCell<HashSet<usize, FxBuildHasher>> = Default::default();
+}
+
+/// Marks a gas mixture as reacting for as long as it's alive, so a reaction that calls `react`
+/// on the same mixture again can be caught instead of recursing into it.
+pub struct ReactionGuard(usize);
+
+impl ReactionGuard {
+	/// Returns `None` if the mixture with the given ID is already reacting on this thread.
+	#[must_use]
+	pub fn try_new(mix_id: usize) -> Option<Self> {
+		REACTING_MIXTURES
+			.with(|reacting| reacting.borrow_mut().insert(mix_id))
+			.then(|| Self(mix_id))
+	}
+}
+
+impl Drop for ReactionGuard {
+	fn drop(&mut self) {
+		REACTING_MIXTURES.with(|reacting| {
+			reacting.borrow_mut().remove(&self.0);
+		});
+	}
+}
+
+/// Runs `react` with the mixture with the given ID marked as reacting, through a `ReactionGuard`.
+/// If the mixture's already reacting on this thread, e.g. `react` ended up calling this again for the same mixture,
+/// returns `ReactionReturn::REACTION_BUSY` without running it.
+/// # Errors
+/// If `react` does.
+pub fn react_guarded(
+	mix_id: usize,
+	react: impl FnOnce() -> Result<ReactionReturn, Runtime>,
+) -> Result<ReactionReturn, Runtime> {
+	match ReactionGuard::try_new(mix_id) {
+		Some(_guard) => react(),
+		None => Ok(ReactionReturn::REACTION_BUSY),
+	}
+}
+
+/// The source of randomness for reactions. Any reaction that needs randomness must get it from here,
+/// rather than a global RNG, so that it's covered by `deterministic_reactions`.
+/// With `deterministic_reactions`, the seed depends only on the mixture's volume, temperature and gases,
+/// so reacting the same mixture always rolls the same numbers. Without it, fresh entropy is mixed in on every call.
+#[allow(dead_code)] // no reaction needs randomness yet
+pub fn reaction_rng(mix: &Mixture) -> SplitMix64 {
+	let mut hasher = FxHasher64::default();
+	mix.volume.to_bits().hash(&mut hasher);
+	mix.get_temperature().to_bits().hash(&mut hasher);
+	for (i, amt) in mix.enumerate() {
+		i.hash(&mut hasher);
+		amt.to_bits().hash(&mut hasher);
+	}
+	#[cfg(not(feature = "deterministic_reactions"))]
+	{
+		use std::{collections::hash_map::RandomState, hash::BuildHasher};
+		RandomState::new().build_hasher().finish().hash(&mut hasher);
+	}
+	SplitMix64::new(hasher.finish())
 }
 
 #[shutdown]
@@ -46,19 +110,42 @@ fn clean_up_reaction_values() {
 
 /// Runs a reaction given a `ReactionIdentifier`. Returns the result of the reaction, error or success.
 /// # Errors
-/// If the reaction itself has a runtime.
+/// If the reaction itself has a runtime; the error message will name the reaction.
 pub fn react_by_id(id: ReactionIdentifier, src: &Value, holder: &Value) -> DMResult {
 	REACTION_VALUES.with(|r| {
 		r.borrow().get(&id).map_or_else(
-			|| Err(runtime!("Reaction with invalid id")),
-			|reaction| match reaction {
-				ReactionSide::ByondSide(val) => val.call("react", &[src, holder]),
-				ReactionSide::RustSide(func) => func(src, holder),
+			|| Err(runtime!("Reaction with invalid id {}", id)),
+			|(name, reaction)| {
+				match reaction {
+					ReactionSide::ByondSide(val) => val.call("react", &[src, holder]),
+					ReactionSide::RustSide(func) => func(src, holder),
+				}
+				.map_err(|e| runtime!("Reaction {} (id {}) failed: {}", name, id, e.message))
 			},
 		)
 	})
 }
 
+/// The string ID of the reaction with the given `ReactionIdentifier`, if there is one.
+#[must_use]
+pub fn reaction_name(id: ReactionIdentifier) -> Option<String> {
+	REACTION_VALUES.with(|r| r.borrow().get(&id).map(|(name, _)| name.to_string()))
+}
+
+/// The `ReactionIdentifier` for the reaction with the given string ID.
+#[must_use]
+pub fn reaction_id_from_string(string_id: &str) -> ReactionIdentifier {
+	fxhash::hash64(string_id.as_bytes())
+}
+
+/// Bitfield of gases with a positive requirement, i.e. those that have to be in the mix for the reaction to happen.
+fn required_gases_bitfield(min_gas_reqs: &[(GasIDX, f32)]) -> u64 {
+	min_gas_reqs
+		.iter()
+		.filter(|&&(i, amt)| i < 64 && amt > 0.0)
+		.fold(0, |acc, &(i, _)| acc | (1 << i))
+}
+
 impl Reaction {
 	/// Takes a `/datum/gas_reaction` and makes a byond reaction out of it.
 	#[must_use]
@@ -81,7 +168,7 @@ impl Reaction {
 				None
 			}
 		};
-		let id = fxhash::hash64(string_id.as_bytes());
+		let id = reaction_id_from_string(&string_id);
 		let our_reaction = {
 			if let Ok(min_reqs) = reaction.get_list(byond_string!("min_requirements")) {
 				let mut min_gas_reqs: Vec<(GasIDX, f32)> = Vec::new();
@@ -109,6 +196,7 @@ impl Reaction {
 					.get(byond_string!("FIRE_REAGENTS"))
 					.and_then(|v| v.as_number())
 					.ok();
+				let required_gases = required_gases_bitfield(&min_gas_reqs);
 				Ok(Reaction {
 					id,
 					priority,
@@ -117,6 +205,7 @@ impl Reaction {
 					min_ener_req,
 					min_fire_req,
 					min_gas_reqs,
+					required_gases,
 				})
 			} else {
 				Err(runtime!(format!(
@@ -134,14 +223,11 @@ impl Reaction {
 					string_id
 				)));
 			}
-			match func {
-				Some(function) => {
-					reaction_map.insert(our_reaction.id, ReactionSide::RustSide(function))
-				}
-				None => {
-					reaction_map.insert(our_reaction.id, ReactionSide::ByondSide(reaction.clone()))
-				}
+			let side = match func {
+				Some(function) => ReactionSide::RustSide(function),
+				None => ReactionSide::ByondSide(reaction.clone()),
 			};
+			reaction_map.insert(our_reaction.id, (string_id.into_boxed_str(), side));
 			Ok(())
 		})?;
 		Ok(our_reaction)
@@ -151,12 +237,21 @@ impl Reaction {
 		self.id
 	}
 	/// Checks if the given gas mixture can react with this reaction.
+	/// When checking many reactions against one mix, use `check_conditions_with_bitfield` instead, so the mix's gases are only scanned once.
 	pub fn check_conditions(&self, mix: &Mixture) -> bool {
+		self.check_conditions_with_bitfield(mix, mix.gases_bitfield())
+	}
+	/// As `check_conditions`, but with the mix's `Mixture::gases_bitfield` already worked out.
+	/// Temperature bounds are checked first, since they're just a comparison; reactions with no gas requirements never look at the gases at all.
+	pub fn check_conditions_with_bitfield(&self, mix: &Mixture, gases: u64) -> bool {
 		self.min_temp_req
 			.map_or(true, |temp_req| mix.get_temperature() >= temp_req)
 			&& self
 				.max_temp_req
 				.map_or(true, |temp_req| mix.get_temperature() <= temp_req)
+			// cheap rejection for the common case of the mix lacking a required gas entirely
+			&& (self.required_gases == 0
+				|| gases & self.required_gases == self.required_gases)
 			&& self
 				.min_gas_reqs
 				.iter()
@@ -169,11 +264,53 @@ impl Reaction {
 				oxi.min(fuel) >= fire_req
 			})
 	}
+	/// How far past this reaction's requirements the given mix is, from 0 (just barely meeting them) to 1 (at least double all of them).
+	/// Each minimum requirement contributes its excess relative to the requirement, capped at 1, and the result is their average.
+	/// Doesn't check that the mix meets the requirements at all; that's `check_conditions`.
+	pub fn margin_past_requirements(&self, mix: &Mixture) -> f32 {
+		let relative_excess = |have: f32, need: f32| {
+			if need > 0.0 {
+				((have - need) / need).clamp(0.0, 1.0)
+			} else {
+				1.0
+			}
+		};
+		let (total, count) = self
+			.min_temp_req
+			.map(|temp_req| relative_excess(mix.get_temperature(), temp_req))
+			.into_iter()
+			.chain(
+				self.min_ener_req
+					.map(|ener_req| relative_excess(mix.thermal_energy(), ener_req)),
+			)
+			.chain(self.min_fire_req.map(|fire_req| {
+				let (oxi, fuel) = mix.get_burnability();
+				relative_excess(oxi.min(fuel), fire_req)
+			}))
+			.chain(
+				self.min_gas_reqs
+					.iter()
+					.filter(|&&(_, amt)| amt > 0.0)
+					.map(|&(k, v)| relative_excess(mix.get_moles(k), v)),
+			)
+			.fold((0.0, 0), |(total, count), margin| {
+				(total + margin, count + 1)
+			});
+		if count == 0 {
+			1.0
+		} else {
+			total / count as f32
+		}
+	}
 	/// Returns the priority of the reaction.
 	#[must_use]
 	pub fn get_priority(&self) -> ReactionPriority {
 		self.priority
 	}
+	/// Changes the priority of the reaction. Anything keyed by priority has to be re-sorted to match.
+	pub(crate) fn set_priority(&mut self, priority: ReactionPriority) {
+		self.priority = priority;
+	}
 	/// Calls the reaction with the given arguments.
 	/// # Errors
 	/// If the reaction itself has a runtime error, this will propagate it up.
@@ -181,3 +318,193 @@ impl Reaction {
 		react_by_id(self.id, src, holder)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::gas::types::{destroy_gas_statics, register_gas_manually, set_gas_statics_manually};
+
+	#[test]
+	fn test_nested_react_guard() {
+		let outer = ReactionGuard::try_new(12);
+		assert!(outer.is_some());
+		// a reaction on mix 12 calling react on mix 12 again
+		assert!(ReactionGuard::try_new(12).is_none());
+		// other mixtures can still react from within a reaction
+		assert!(ReactionGuard::try_new(13).is_some());
+		drop(outer);
+		assert!(ReactionGuard::try_new(12).is_some());
+	}
+	#[test]
+	fn test_nested_react_guarded() {
+		// a reaction on mix 12 that reacts mix 12 again, and then mix 13
+		let outer = react_guarded(12, || {
+			assert_eq!(
+				react_guarded(12, || Ok(ReactionReturn::REACTING)).unwrap(),
+				ReactionReturn::REACTION_BUSY
+			);
+			assert_eq!(
+				react_guarded(13, || Ok(ReactionReturn::REACTING)).unwrap(),
+				ReactionReturn::REACTING
+			);
+			Ok(ReactionReturn::REACTING)
+		});
+		assert_eq!(outer.unwrap(), ReactionReturn::REACTING);
+		// no longer busy once the outer react is done, even if it errored
+		assert!(react_guarded(12, || Err(runtime!("reaction failed"))).is_err());
+		assert_eq!(
+			react_guarded(12, || Ok(ReactionReturn::NO_REACTION)).unwrap(),
+			ReactionReturn::NO_REACTION
+		);
+	}
+	#[test]
+	fn test_required_gases_rejection() {
+		set_gas_statics_manually();
+		register_gas_manually("o2", 20.0);
+		register_gas_manually("n2", 20.0);
+		register_gas_manually("plasma", 200.0);
+		let min_gas_reqs = vec![(0, 0.0), (2, 10.0)];
+		let reaction = Reaction {
+			id: 0,
+			priority: FloatOrd(0.0),
+			min_temp_req: None,
+			max_temp_req: None,
+			min_ener_req: None,
+			min_fire_req: None,
+			required_gases: required_gases_bitfield(&min_gas_reqs),
+			min_gas_reqs,
+		};
+		// a requirement of zero moles doesn't need the gas to be present
+		assert_eq!(reaction.required_gases, 0b100);
+		let mut mix = Mixture::new();
+		mix.set_moles(1, 100.0);
+		assert!(!reaction.check_conditions(&mix));
+		mix.set_moles(2, 5.0);
+		assert!(!reaction.check_conditions(&mix));
+		mix.set_moles(2, 20.0);
+		assert!(reaction.check_conditions(&mix));
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_temperature_only_reaction() {
+		set_gas_statics_manually();
+		register_gas_manually("o2", 20.0);
+		let reaction = Reaction {
+			id: 0,
+			priority: FloatOrd(0.0),
+			min_temp_req: Some(1000.0),
+			max_temp_req: Some(2000.0),
+			min_ener_req: None,
+			min_fire_req: None,
+			min_gas_reqs: Vec::new(),
+			required_gases: 0,
+		};
+		let mut mix = Mixture::new();
+		mix.set_temperature(500.0);
+		assert!(!reaction.check_conditions(&mix));
+		mix.set_temperature(1500.0);
+		assert!(reaction.check_conditions(&mix));
+		mix.set_moles(0, 10.0);
+		assert!(reaction.check_conditions(&mix));
+		mix.set_temperature(2500.0);
+		assert!(!reaction.check_conditions(&mix));
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_margin_past_requirements() {
+		set_gas_statics_manually();
+		register_gas_manually("o2", 20.0);
+		let min_gas_reqs = vec![(0, 10.0)];
+		let reaction = Reaction {
+			id: 0,
+			priority: FloatOrd(0.0),
+			min_temp_req: Some(1000.0),
+			max_temp_req: None,
+			min_ener_req: None,
+			min_fire_req: None,
+			required_gases: required_gases_bitfield(&min_gas_reqs),
+			min_gas_reqs,
+		};
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 10.0);
+		mix.set_temperature(1000.0);
+		assert_eq!(reaction.margin_past_requirements(&mix), 0.0);
+		mix.set_temperature(1500.0);
+		assert_eq!(reaction.margin_past_requirements(&mix), 0.25);
+		mix.set_moles(0, 100.0);
+		mix.set_temperature(5000.0);
+		assert_eq!(reaction.margin_past_requirements(&mix), 1.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_reload_unchanged_reactions() {
+		use crate::gas::types::replace_reactions;
+		use std::collections::BTreeMap;
+		let reaction = Reaction {
+			id: 1,
+			priority: FloatOrd(1.0),
+			min_temp_req: Some(373.15),
+			max_temp_req: None,
+			min_ener_req: None,
+			min_fire_req: None,
+			min_gas_reqs: vec![(0, 10.0)],
+			required_gases: 0b1,
+		};
+		let reactions: BTreeMap<_, _> = [(reaction.priority, reaction.clone())]
+			.into_iter()
+			.collect();
+		let mut current = None;
+		assert!(replace_reactions(&mut current, reactions.clone()));
+		assert!(!replace_reactions(&mut current, reactions.clone()));
+		let mut changed = reaction;
+		changed.min_gas_reqs = vec![(0, 20.0)];
+		let changed: BTreeMap<_, _> = [(changed.priority, changed)].into_iter().collect();
+		assert!(replace_reactions(&mut current, changed.clone()));
+		assert!(current == Some(changed));
+	}
+	#[test]
+	fn test_reprioritize_reaction() {
+		use crate::gas::types::reprioritize_reaction;
+		use std::collections::BTreeMap;
+		let reaction = |id, priority| Reaction {
+			id,
+			priority: FloatOrd(priority),
+			min_temp_req: None,
+			max_temp_req: None,
+			min_ener_req: None,
+			min_fire_req: None,
+			min_gas_reqs: Vec::new(),
+			required_gases: 0,
+		};
+		let mut reactions: BTreeMap<_, _> = [reaction(1, 1.0), reaction(2, 2.0)]
+			.into_iter()
+			.map(|reaction| (reaction.priority, reaction))
+			.collect();
+		let order = |reactions: &BTreeMap<ReactionPriority, Reaction>| {
+			reactions.values().map(Reaction::get_id).collect::<Vec<_>>()
+		};
+		assert_eq!(order(&reactions), vec![1, 2]);
+		assert!(reprioritize_reaction(&mut reactions, 1, FloatOrd(3.0)).unwrap());
+		assert_eq!(order(&reactions), vec![2, 1]);
+		assert_eq!(reactions[&FloatOrd(3.0)].get_priority(), FloatOrd(3.0));
+		assert!(reprioritize_reaction(&mut reactions, 2, FloatOrd(3.0)).is_err());
+		assert!(!reprioritize_reaction(&mut reactions, 3, FloatOrd(4.0)).unwrap());
+		assert_eq!(order(&reactions), vec![2, 1]);
+	}
+	#[cfg(feature = "deterministic_reactions")]
+	#[test]
+	fn test_deterministic_reaction_rng() {
+		set_gas_statics_manually();
+		register_gas_manually("o2", 20.0);
+		register_gas_manually("plasma", 200.0);
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 20.0);
+		mix.set_moles(1, 40.0);
+		mix.set_temperature(1000.0);
+		let first = reaction_rng(&mix).next_u64();
+		assert_eq!(first, reaction_rng(&mix.clone()).next_u64());
+		mix.set_temperature(1001.0);
+		assert_ne!(first, reaction_rng(&mix).next_u64());
+		destroy_gas_statics();
+	}
+}
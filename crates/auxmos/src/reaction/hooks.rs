@@ -224,8 +224,9 @@ fn fusion(byond_air: &Value, holder: &Value) -> DMResult<Value> {
 			air.get_moles(co2),
 			(air.volume / FUSION_SCALE_DIVISOR).max(FUSION_MINIMAL_SCALE),
 			air.get_temperature().log10(),
-			air.enumerate()
-				.fold(0.0, |acc, (i, amt)| acc + gas_fusion_power(&i) * amt),
+			air.enumerate().try_fold(0.0, |acc, (i, amt)| {
+				gas_fusion_power(&i).map(|power| acc + power * amt)
+			})?,
 		))
 	})?;
 	//The size of the phase space hypertorus
@@ -0,0 +1,30 @@
+//! Seeded randomness, for anything in auxmos that needs to be reproducible.
+
+/// A tiny seeded PRNG. Not remotely cryptographic, but the same seed always gives the same sequence.
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+	#[must_use]
+	pub fn new(seed: u64) -> Self {
+		Self(seed)
+	}
+	pub fn next_u64(&mut self) -> u64 {
+		self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+		let mut z = self.0;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+		z ^ (z >> 31)
+	}
+	/// A float in [0, 1).
+	pub fn next_f32(&mut self) -> f32 {
+		(self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+	}
+	/// An integer in [0, max). Returns 0 if max is 0.
+	pub fn next_below(&mut self, max: usize) -> usize {
+		if max == 0 {
+			0
+		} else {
+			(self.next_u64() % max as u64) as usize
+		}
+	}
+}
@@ -16,7 +16,11 @@ use auxtools::*;
 
 use rayon::prelude::*;
 
-use crate::{constants::*, gas::Mixture, GasArena};
+use crate::{
+	constants::*,
+	gas::{mix_index, Mixture},
+	GasArena,
+};
 
 use fxhash::FxBuildHasher;
 
@@ -474,17 +478,7 @@ fn register_turf(id: u32) -> Result<(), Runtime> {
 	if flag >= 0 {
 		let mut to_insert: TurfMixture = TurfMixture::default();
 		let air = src.get(byond_string!("air"))?;
-		to_insert.mix = air
-			.get_number(byond_string!("_extools_pointer_gasmixture"))
-			.map_err(|_| {
-				runtime!(
-					"Attempt to interpret non-number value as number {} {}:{}",
-					std::file!(),
-					std::line!(),
-					std::column!()
-				)
-			})?
-			.to_bits() as usize;
+		to_insert.mix = mix_index(&air)?;
 		to_insert.flags = SimulationFlags::from_bits_truncate(flag as u8);
 		to_insert.id = id;
 
@@ -592,26 +586,15 @@ fn update_visuals(src: Value) -> DMResult {
 			let gas_overlays = Value::globals()
 				.get(byond_string!("gas_data"))?
 				.get_list(byond_string!("overlays"))?;
-			let ptr = air
-				.get_number(byond_string!("_extools_pointer_gasmixture"))
-				.map_err(|_| {
-					runtime!(
-						"Attempt to interpret non-number value as number {} {}:{}",
-						std::file!(),
-						std::line!(),
-						std::column!()
-					)
-				})?
-				.to_bits() as usize;
+			let ptr = mix_index(&air)?;
 			GasArena::with_gas_mixture(ptr, |mix| {
 				mix.for_each_gas(|idx, moles| {
-					if let Some(amt) = gas::types::gas_visibility(idx) {
-						if moles > amt {
+					if let Some(vis) = gas::types::gas_visibility(idx)? {
+						let amount = mix.visibility_amount(moles, vis);
+						if amount > vis.threshold() {
 							let this_overlay_list =
 								gas_overlays.get(gas::gas_idx_to_id(idx)?)?.as_list()?;
-							if let Ok(this_gas_overlay) =
-								this_overlay_list.get(gas::mixture::visibility_step(moles))
-							{
+							if let Ok(this_gas_overlay) = this_overlay_list.get(vis.step(amount)) {
 								overlay_types.append(this_gas_overlay);
 							}
 						}
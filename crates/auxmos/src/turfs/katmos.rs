@@ -10,6 +10,8 @@ use fxhash::FxBuildHasher;
 
 use auxcallback::byond_callback_sender;
 
+use crate::gas::minimum_moles_delta;
+
 use petgraph::{graph::NodeIndex, graphmap::DiGraphMap};
 
 use std::{
@@ -745,6 +747,7 @@ pub fn equalize(
 	let turfs_processed: AtomicUsize = AtomicUsize::new(0);
 	let mut found_turfs: HashSet<NodeIndex<usize>, FxBuildHasher> = Default::default();
 	let contains_planet: AtomicBool = AtomicBool::new(false);
+	let min_moles_delta = minimum_moles_delta();
 	with_turf_gases_read(|arena| {
 		let zoned_turfs = high_pressure_turfs
 			.iter()
@@ -764,8 +767,7 @@ pub fn equalize(
 					let our_moles = all_mixtures[cur_mixture.mix].read().total_moles();
 					our_moles < 10.0
 						|| arena.adjacent_mixes(cur_index, all_mixtures).all(|lock| {
-							(lock.read().total_moles() - our_moles).abs()
-								< MINIMUM_MOLES_DELTA_TO_MOVE
+							(lock.read().total_moles() - our_moles).abs() < min_moles_delta
 						})
 				});
 
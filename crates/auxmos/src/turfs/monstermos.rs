@@ -9,6 +9,8 @@ use fxhash::FxBuildHasher;
 
 use auxcallback::byond_callback_sender;
 
+use crate::gas::minimum_moles_delta;
+
 use std::cell::Cell;
 
 type TransferInfo = [f32; 7];
@@ -748,6 +750,7 @@ pub fn equalize(
 		HashMap::with_hasher(FxBuildHasher::default());
 	let mut turfs_processed = 0;
 	let mut queue_cycle_slow = 1;
+	let min_moles_delta = minimum_moles_delta();
 	let mut found_turfs: HashSet<TurfID, FxBuildHasher> =
 		HashSet::with_hasher(FxBuildHasher::default());
 	for &i in high_pressure_turfs.iter() {
@@ -758,8 +761,7 @@ pub fn equalize(
 					let our_moles = all_mixtures[m.mix].read().total_moles();
 					our_moles < 10.0
 						|| m.adjacent_mixes(all_mixtures).all(|lock| {
-							(lock.read().total_moles() - our_moles).abs()
-								< MINIMUM_MOLES_DELTA_TO_MOVE
+							(lock.read().total_moles() - our_moles).abs() < min_moles_delta
 						})
 				})
 			}) {
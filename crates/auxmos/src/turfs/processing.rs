@@ -2,7 +2,10 @@ use auxtools::*;
 
 use super::*;
 
-use crate::GasArena;
+use crate::{
+	gas::{minimum_moles_delta, GasVisibility, ScratchMix},
+	GasArena,
+};
 
 use auxcallback::{byond_callback_sender, process_callbacks_for_millis};
 
@@ -316,6 +319,7 @@ fn should_process(
 	all_mixtures: &[RwLock<Mixture>],
 	arena: &TurfGases,
 ) -> bool {
+	let min_moles_delta = minimum_moles_delta();
 	mixture.enabled()
 		&& arena.adjacent_node_ids(index).next().is_some()
 		&& all_mixtures
@@ -324,8 +328,7 @@ fn should_process(
 			.map_or(false, |gas| {
 				for entry in arena.adjacent_mixes(index, all_mixtures) {
 					if let Some(mix) = entry.try_read() {
-						if gas.temperature_compare(&mix)
-							|| gas.compare_with(&mix, MINIMUM_MOLES_DELTA_TO_MOVE)
+						if gas.temperature_compare(&mix) || gas.compare_with(&mix, min_moles_delta)
 						{
 							return true;
 						}
@@ -540,7 +543,7 @@ fn excited_group_processing(
 			let mut turfs: Vec<&TurfMixture> = Vec::with_capacity(200);
 			let mut min_pressure = initial_mix_ref.return_pressure();
 			let mut max_pressure = min_pressure;
-			let mut fully_mixed = Mixture::new();
+			let mut fully_mixed = ScratchMix::new();
 			border_turfs.push_back(initial_turf);
 			found_turfs.insert(initial_turf);
 			GasArena::with_all_mixtures(|all_mixtures| {
@@ -594,7 +597,7 @@ fn excited_group_processing(
 // Checks if the gas can react or can update visuals, returns None if not.
 fn post_process_cell<'a>(
 	mixture: &'a TurfMixture,
-	vis: &[Option<f32>],
+	vis: &[Option<GasVisibility>],
 	all_mixtures: &[RwLock<Mixture>],
 	reactions: &BTreeMap<crate::reaction::ReactionPriority, crate::reaction::Reaction>,
 ) -> Option<(&'a TurfMixture, bool, bool)> {
@@ -626,7 +629,9 @@ fn post_process() {
 					.filter_map(|mixture| post_process_cell(mixture, &vis, all_mixtures, reactions))
 					.collect::<Vec<_>>()
 			})
-		});
+		})
+		// nothing to do until reactions are loaded
+		.unwrap_or_default();
 		processables
 			.into_par_iter()
 			.for_each(|(tmix, should_update_vis, should_react)| {